@@ -1,23 +1,225 @@
-pub fn pmt(rate: f64, nper: i64, pv: i64, fv: i64, payment_flag: bool) -> f64 {
+use std::convert::TryFrom;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XlsxError {
+    /// `#NUM!`
+    Num,
+    /// `#VALUE!`
+    Value,
+    /// `#DIV/0!`
+    Div0,
+}
+
+impl fmt::Display for XlsxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            XlsxError::Num => write!(f, "#NUM!"),
+            XlsxError::Value => write!(f, "#VALUE!"),
+            XlsxError::Div0 => write!(f, "#DIV/0!"),
+        }
+    }
+}
+
+impl std::error::Error for XlsxError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaymentTiming {
+    /// Payments are due at the end of each period (Excel `type` 0).
+    #[default]
+    End,
+    /// Payments are due at the beginning of each period (Excel `type` 1).
+    Beginning,
+}
+
+impl From<bool> for PaymentTiming {
+    fn from(beginning: bool) -> Self {
+        if beginning {
+            PaymentTiming::Beginning
+        } else {
+            PaymentTiming::End
+        }
+    }
+}
+
+impl TryFrom<i64> for PaymentTiming {
+    type Error = XlsxError;
+
+    fn try_from(payment_type: i64) -> Result<Self, Self::Error> {
+        match payment_type {
+            0 => Ok(PaymentTiming::End),
+            1 => Ok(PaymentTiming::Beginning),
+            _ => Err(XlsxError::Num),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DayCountBasis {
+    /// US (NASD) 30/360 (Excel `basis` 0).
+    #[default]
+    UsNasd30360,
+    /// Actual/actual (Excel `basis` 1).
+    ActualActual,
+    /// Actual/360 (Excel `basis` 2).
+    Actual360,
+    /// Actual/365 (Excel `basis` 3).
+    Actual365,
+    /// European 30/360 (Excel `basis` 4).
+    European30360,
+}
+
+impl TryFrom<i64> for DayCountBasis {
+    type Error = XlsxError;
+
+    fn try_from(basis: i64) -> Result<Self, Self::Error> {
+        match basis {
+            0 => Ok(DayCountBasis::UsNasd30360),
+            1 => Ok(DayCountBasis::ActualActual),
+            2 => Ok(DayCountBasis::Actual360),
+            3 => Ok(DayCountBasis::Actual365),
+            4 => Ok(DayCountBasis::European30360),
+            _ => Err(XlsxError::Num),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CouponFrequency {
+    /// One coupon a year (Excel `frequency` 1).
+    Annual,
+    /// Two coupons a year (Excel `frequency` 2).
+    Semiannual,
+    /// Four coupons a year (Excel `frequency` 4).
+    Quarterly,
+}
+
+impl CouponFrequency {
+    fn per_year(self) -> i64 {
+        match self {
+            CouponFrequency::Annual => 1,
+            CouponFrequency::Semiannual => 2,
+            CouponFrequency::Quarterly => 4,
+        }
+    }
+}
+
+impl TryFrom<i64> for CouponFrequency {
+    type Error = XlsxError;
+
+    fn try_from(frequency: i64) -> Result<Self, Self::Error> {
+        match frequency {
+            1 => Ok(CouponFrequency::Annual),
+            2 => Ok(CouponFrequency::Semiannual),
+            4 => Ok(CouponFrequency::Quarterly),
+            _ => Err(XlsxError::Num),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_xlsx_error {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(XlsxError::Num.to_string(), "#NUM!");
+        assert_eq!(XlsxError::Value.to_string(), "#VALUE!");
+        assert_eq!(XlsxError::Div0.to_string(), "#DIV/0!");
+    }
+}
+
+#[cfg(test)]
+mod tests_payment_timing {
+    use super::*;
+
+    #[test]
+    fn test_default() {
+        assert_eq!(PaymentTiming::default(), PaymentTiming::End);
+    }
+
+    #[test]
+    fn test_from_bool() {
+        assert_eq!(PaymentTiming::from(false), PaymentTiming::End);
+        assert_eq!(PaymentTiming::from(true), PaymentTiming::Beginning);
+    }
+
+    #[test]
+    fn test_try_from_i64() {
+        assert_eq!(PaymentTiming::try_from(0), Ok(PaymentTiming::End));
+        assert_eq!(PaymentTiming::try_from(1), Ok(PaymentTiming::Beginning));
+        assert_eq!(PaymentTiming::try_from(2), Err(XlsxError::Num));
+        assert_eq!(PaymentTiming::try_from(-1), Err(XlsxError::Num));
+    }
+}
+
+#[cfg(test)]
+mod tests_day_count_basis {
+    use super::*;
+
+    #[test]
+    fn test_default() {
+        assert_eq!(DayCountBasis::default(), DayCountBasis::UsNasd30360);
+    }
+
+    #[test]
+    fn test_try_from_i64() {
+        assert_eq!(DayCountBasis::try_from(0), Ok(DayCountBasis::UsNasd30360));
+        assert_eq!(DayCountBasis::try_from(1), Ok(DayCountBasis::ActualActual));
+        assert_eq!(DayCountBasis::try_from(2), Ok(DayCountBasis::Actual360));
+        assert_eq!(DayCountBasis::try_from(3), Ok(DayCountBasis::Actual365));
+        assert_eq!(DayCountBasis::try_from(4), Ok(DayCountBasis::European30360));
+        assert_eq!(DayCountBasis::try_from(5), Err(XlsxError::Num));
+        assert_eq!(DayCountBasis::try_from(-1), Err(XlsxError::Num));
+    }
+}
+
+#[cfg(test)]
+mod tests_coupon_frequency {
+    use super::*;
+
+    #[test]
+    fn test_try_from_i64() {
+        assert_eq!(CouponFrequency::try_from(1), Ok(CouponFrequency::Annual));
+        assert_eq!(
+            CouponFrequency::try_from(2),
+            Ok(CouponFrequency::Semiannual)
+        );
+        assert_eq!(CouponFrequency::try_from(4), Ok(CouponFrequency::Quarterly));
+        assert_eq!(CouponFrequency::try_from(3), Err(XlsxError::Num));
+        assert_eq!(CouponFrequency::try_from(0), Err(XlsxError::Num));
+    }
+}
+
+pub fn pmt(
+    rate: f64,
+    nper: i64,
+    pv: f64,
+    fv: f64,
+    timing: PaymentTiming,
+) -> Result<f64, XlsxError> {
     if nper == 0 {
-        return 0.0;
+        return Err(XlsxError::Num);
     }
 
     let nper_f64: f64 = nper as f64;
-    let pv_f64: f64 = pv as f64;
-    let fv_f64: f64 = fv as f64;
 
     if rate == 0.0 {
-        return -(pv_f64 + fv_f64) / nper_f64;
+        return Ok(-(pv + fv) / nper_f64);
     }
 
     let pvif = (1.0 + rate).powf(nper_f64);
-    let pmt = (rate / (pvif - 1.0)) * -(pv_f64 * pvif + fv_f64);
+    let pmt = (rate / (pvif - 1.0)) * -(pv * pvif + fv);
 
-    if !payment_flag {
-        return pmt;
+    if timing == PaymentTiming::End {
+        return Ok(pmt);
     }
-    pmt / (1.0 + rate)
+    Ok(pmt / (1.0 + rate))
+}
+
+#[deprecated(since = "0.2.0", note = "use `pmt` with f64 `pv` and `fv`")]
+pub fn pmt_i64(rate: f64, nper: i64, pv: i64, fv: i64, payment_flag: bool) -> f64 {
+    pmt(rate, nper, pv as f64, fv as f64, payment_flag.into()).unwrap_or(0.0)
 }
 
 #[cfg(test)]
@@ -28,20 +230,20 @@ mod tests_pmt {
     struct TestArgs {
         rate: f64,
         nper: i64,
-        pv: i64,
-        fv: i64,
-        payment_flag: bool,
+        pv: f64,
+        fv: f64,
+        timing: PaymentTiming,
     }
 
     struct TestData {
         args: TestArgs,
-        expected: f64,
+        expected: Result<f64, XlsxError>,
     }
 
     #[test]
     fn test_nper_is_0() {
-        let actual = pmt(0.3, 0, 100_000, 0, false);
-        assert_eq!(actual, 0.0);
+        let actual = pmt(0.3, 0, 100_000.0, 0.0, PaymentTiming::End);
+        assert_eq!(actual, Err(XlsxError::Num));
     }
 
     #[test]
@@ -51,41 +253,41 @@ mod tests_pmt {
                 args: TestArgs {
                     rate: 0.0,
                     nper: 36,
-                    pv: 100_000,
-                    fv: 0,
-                    payment_flag: false,
+                    pv: 100_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: -2_777.777777777778,
+                expected: Ok(-2_777.777777777778),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.0,
                     nper: 36,
-                    pv: 100_000,
-                    fv: 0,
-                    payment_flag: true,
+                    pv: 100_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -2_777.777777777778,
+                expected: Ok(-2_777.777777777778),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.0,
                     nper: 36,
-                    pv: 100_000,
-                    fv: 1_000,
-                    payment_flag: false,
+                    pv: 100_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: -2805.5555555555557,
+                expected: Ok(-2805.5555555555557),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.0,
                     nper: 36,
-                    pv: 100_000,
-                    fv: 1_000,
-                    payment_flag: true,
+                    pv: 100_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -2805.5555555555557,
+                expected: Ok(-2805.5555555555557),
             },
         ];
         for t in &test_cases {
@@ -94,7 +296,7 @@ mod tests_pmt {
                 t.args.nper,
                 t.args.pv,
                 t.args.fv,
-                t.args.payment_flag,
+                t.args.timing,
             );
             assert_eq!(actual, t.expected, "args: {:#?}", t.args);
         }
@@ -107,41 +309,41 @@ mod tests_pmt {
                 args: TestArgs {
                     rate: 0.3,
                     nper: 36,
-                    pv: 100_000,
-                    fv: 0,
-                    payment_flag: false,
+                    pv: 100_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: -30_002.37243823623,
+                expected: Ok(-30_002.37243823623),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.3,
                     nper: 36,
-                    pv: 100_000,
-                    fv: 0,
-                    payment_flag: true,
+                    pv: 100_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -23078.748029412483,
+                expected: Ok(-23078.748029412483),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.3,
                     nper: 36,
-                    pv: 100_000,
-                    fv: 1_000,
-                    payment_flag: false,
+                    pv: 100_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: -30_002.396162618596,
+                expected: Ok(-30_002.396162618596),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.3,
                     nper: 36,
-                    pv: 100_000,
-                    fv: 1_000,
-                    payment_flag: true,
+                    pv: 100_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -23078.76627893738,
+                expected: Ok(-23078.76627893738),
             },
         ];
         for t in &test_cases {
@@ -150,42 +352,58 @@ mod tests_pmt {
                 t.args.nper,
                 t.args.pv,
                 t.args.fv,
-                t.args.payment_flag,
+                t.args.timing,
             );
             assert_eq!(actual, t.expected, "args: {:#?}", t.args);
         }
     }
 }
 
-pub fn ipmt(rate: f64, per: i64, nper: i64, pv: i64, fv: i64, payment_flag: bool) -> f64 {
-    if nper == 0 {
-        return 0.0;
+pub fn ipmt(
+    rate: f64,
+    per: i64,
+    nper: i64,
+    pv: f64,
+    fv: f64,
+    timing: PaymentTiming,
+) -> Result<f64, XlsxError> {
+    if per < 1 || per > nper {
+        return Err(XlsxError::Num);
     }
 
-    if per == 0 {
-        return 0.0;
+    if rate <= -1.0 {
+        return Err(XlsxError::Num);
     }
 
-    if rate < 0.0 {
-        return 0.0;
+    if per == 1 && timing == PaymentTiming::Beginning {
+        return Ok(0.0);
     }
 
-    let pmt = pmt(rate, nper, pv, fv, false);
+    let pmt = pmt(rate, nper, pv, fv, PaymentTiming::End)?;
     let per_sub_1_f64 = (per - 1) as f64;
 
-    let n = if rate.abs() > 0.5 {
-        (1.0 + rate).powf(per_sub_1_f64)
+    let (n, m) = if rate < 0.0 {
+        let growth = per_sub_1_f64 * rate.ln_1p();
+        (growth.exp(), growth.exp_m1())
     } else {
-        (per_sub_1_f64 * (1.0 + rate).ln()).exp()
+        let n = if rate > 0.5 {
+            (1.0 + rate).powf(per_sub_1_f64)
+        } else {
+            (per_sub_1_f64 * (1.0 + rate).ln()).exp()
+        };
+        (n, (per_sub_1_f64 * (1.0 + rate).ln()).exp() - 1.0)
     };
 
-    let m = (per_sub_1_f64 * (1.0 + rate).ln()).exp() - 1.0;
-
-    let ip = -((pv as f64) * n * rate + pmt * m);
-    if !payment_flag {
-        return ip;
+    let ip = -(pv * n * rate + pmt * m);
+    if timing == PaymentTiming::End {
+        return Ok(ip);
     }
-    ip / (1.0 + rate)
+    Ok(ip / (1.0 + rate))
+}
+
+#[deprecated(since = "0.2.0", note = "use `ipmt` with f64 `pv` and `fv`")]
+pub fn ipmt_i64(rate: f64, per: i64, nper: i64, pv: i64, fv: i64, payment_flag: bool) -> f64 {
+    ipmt(rate, per, nper, pv as f64, fv as f64, payment_flag.into()).unwrap_or(0.0)
 }
 
 #[cfg(test)]
@@ -197,32 +415,182 @@ mod tests_ipmt {
         rate: f64,
         per: i64,
         nper: i64,
-        pv: i64,
-        fv: i64,
-        payment_flag: bool,
+        pv: f64,
+        fv: f64,
+        timing: PaymentTiming,
     }
 
     struct TestData {
         args: TestArgs,
-        expected: f64,
+        expected: Result<f64, XlsxError>,
     }
 
     #[test]
     fn test_per_is_0() {
-        let actual = ipmt(0.3, 0, 36, 100_000, 0, false);
-        assert_eq!(actual, 0.0);
+        let actual = ipmt(0.3, 0, 36, 100_000.0, 0.0, PaymentTiming::End);
+        assert_eq!(actual, Err(XlsxError::Num));
     }
 
     #[test]
     fn test_nper_is_0() {
-        let actual = ipmt(0.3, 3, 0, 100_000, 0, false);
-        assert_eq!(actual, 0.0);
+        let actual = ipmt(0.3, 3, 0, 100_000.0, 0.0, PaymentTiming::End);
+        assert_eq!(actual, Err(XlsxError::Num));
+    }
+
+    #[test]
+    fn test_per_gt_nper() {
+        let actual = ipmt(0.3, 37, 36, 100_000.0, 0.0, PaymentTiming::End);
+        assert_eq!(actual, Err(XlsxError::Num));
+    }
+
+    #[test]
+    fn test_first_period_paid_at_beginning() {
+        let actual = ipmt(0.1, 1, 36, 800_000.0, 0.0, PaymentTiming::Beginning);
+        assert_eq!(actual, Ok(0.0));
+    }
+
+    #[test]
+    fn test_rate_le_minus_1() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    rate: -1.0,
+                    per: 3,
+                    nper: 36,
+                    pv: 100_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -1.5,
+                    per: 3,
+                    nper: 36,
+                    pv: 100_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = ipmt(
+                t.args.rate,
+                t.args.per,
+                t.args.nper,
+                t.args.pv,
+                t.args.fv,
+                t.args.timing,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
     }
 
     #[test]
     fn test_rate_less_than_0() {
-        let actual = ipmt(-0.1, 3, 36, 100_000, 0, false);
-        assert_eq!(actual, 0.0);
+        let test_cases: [TestData; 8] = [
+            TestData {
+                args: TestArgs {
+                    rate: -0.01,
+                    per: 2,
+                    nper: 36,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(7_736.483915781737),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -0.01,
+                    per: 2,
+                    nper: 36,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(7_814.63021796135),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -0.01,
+                    per: 2,
+                    nper: 36,
+                    pv: 800_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(7_736.154520676464),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -0.01,
+                    per: 2,
+                    nper: 36,
+                    pv: 800_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(7_814.297495632792),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -0.6,
+                    per: 2,
+                    nper: 36,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(191_999.99999999863),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -0.6,
+                    per: 2,
+                    nper: 36,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(479_999.99999999657),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -0.6,
+                    per: 2,
+                    nper: 36,
+                    pv: 800_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(191_639.99999999863),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -0.6,
+                    per: 2,
+                    nper: 36,
+                    pv: 800_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(479_099.99999999657),
+            },
+        ];
+        for t in &test_cases {
+            let actual = ipmt(
+                t.args.rate,
+                t.args.per,
+                t.args.nper,
+                t.args.pv,
+                t.args.fv,
+                t.args.timing,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
     }
 
     #[test]
@@ -233,88 +601,88 @@ mod tests_ipmt {
                     rate: 0.1,
                     per: 2,
                     nper: 36,
-                    pv: 800_000,
-                    fv: 0,
-                    payment_flag: false,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: -79_732.55489453014,
+                expected: Ok(-79_732.55489453014),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     per: 2,
                     nper: 36,
-                    pv: 800_000,
-                    fv: 0,
-                    payment_flag: true,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -72_484.14081320922,
+                expected: Ok(-72_484.14081320922),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     per: 2,
                     nper: 36,
-                    pv: 800_000,
-                    fv: 1_000,
-                    payment_flag: false,
+                    pv: 800_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: -79_732.22058814831,
+                expected: Ok(-79_732.22058814831),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     per: 2,
                     nper: 36,
-                    pv: 800_000,
-                    fv: 1_000,
-                    payment_flag: true,
+                    pv: 800_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -72_483.83689831664,
+                expected: Ok(-72_483.83689831664),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.6,
                     per: 2,
                     nper: 36,
-                    pv: 800_000,
-                    fv: 0,
-                    payment_flag: false,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: -479_999.9870856327,
+                expected: Ok(-479_999.9870856327),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.6,
                     per: 2,
                     nper: 36,
-                    pv: 800_000,
-                    fv: 0,
-                    payment_flag: true,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -299_999.99192852044,
+                expected: Ok(-299_999.99192852044),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.6,
                     per: 2,
                     nper: 36,
-                    pv: 800_000,
-                    fv: 1_000,
-                    payment_flag: false,
+                    pv: 800_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: -479_999.9870694897,
+                expected: Ok(-479_999.9870694897),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.6,
                     per: 2,
                     nper: 36,
-                    pv: 800_000,
-                    fv: 1_000,
-                    payment_flag: true,
+                    pv: 800_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -299_999.9919184311,
+                expected: Ok(-299_999.9919184311),
             },
         ];
         for t in &test_cases {
@@ -324,26 +692,40 @@ mod tests_ipmt {
                 t.args.nper,
                 t.args.pv,
                 t.args.fv,
-                t.args.payment_flag,
+                t.args.timing,
             );
             assert_eq!(actual, t.expected, "args: {:#?}", t.args);
         }
     }
 }
 
-pub fn fv(rate: f64, nper: i64, pmt: f64, pv: i64, payment_flag: bool) -> f64 {
-    let pv_f64 = pv as f64;
+pub fn fv(
+    rate: f64,
+    nper: i64,
+    pmt: f64,
+    pv: f64,
+    timing: PaymentTiming,
+) -> Result<f64, XlsxError> {
     let nper_f64 = nper as f64;
 
     if rate == 0.0 {
-        return -(pv_f64 + pmt * nper_f64);
+        return Ok(-(pv + pmt * nper_f64));
+    }
+
+    if rate == -1.0 && nper <= 0 {
+        return Err(XlsxError::Num);
     }
 
     let term = (1.0 + rate).powf(nper_f64);
-    if payment_flag {
-        return -(pv_f64 * term + (pmt * (1.0 + rate) * (term - 1.0)) / rate);
+    if timing == PaymentTiming::Beginning {
+        return Ok(-(pv * term + (pmt * (1.0 + rate) * (term - 1.0)) / rate));
     }
-    -(pv_f64 * term + (pmt * (term - 1.0)) / rate)
+    Ok(-(pv * term + (pmt * (term - 1.0)) / rate))
+}
+
+#[deprecated(since = "0.2.0", note = "use `fv` with f64 `pv`")]
+pub fn fv_i64(rate: f64, nper: i64, pmt: f64, pv: i64, payment_flag: bool) -> f64 {
+    fv(rate, nper, pmt, pv as f64, payment_flag.into()).unwrap_or(0.0)
 }
 
 #[cfg(test)]
@@ -355,13 +737,13 @@ mod tests_fv {
         rate: f64,
         nper: i64,
         pmt: f64,
-        pv: i64,
-        payment_flag: bool,
+        pv: f64,
+        timing: PaymentTiming,
     }
 
     struct TestData {
         args: TestArgs,
-        expected: f64,
+        expected: Result<f64, XlsxError>,
     }
 
     #[test]
@@ -372,40 +754,40 @@ mod tests_fv {
                     rate: 0.0,
                     nper: 12,
                     pmt: 10_000.0,
-                    pv: 0,
-                    payment_flag: false,
+                    pv: 0.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: -120_000.0,
+                expected: Ok(-120_000.0),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.0,
                     nper: 12,
                     pmt: 10_000.0,
-                    pv: 0,
-                    payment_flag: true,
+                    pv: 0.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -120_000.0,
+                expected: Ok(-120_000.0),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.0,
                     nper: 12,
                     pmt: 10_000.0,
-                    pv: 1_000,
-                    payment_flag: false,
+                    pv: 1_000.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: -121_000.0,
+                expected: Ok(-121_000.0),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.0,
                     nper: 12,
                     pmt: 10_000.0,
-                    pv: 1_000,
-                    payment_flag: true,
+                    pv: 1_000.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -121_000.0,
+                expected: Ok(-121_000.0),
             },
         ];
         for t in &test_cases {
@@ -414,7 +796,7 @@ mod tests_fv {
                 t.args.nper,
                 t.args.pmt,
                 t.args.pv,
-                t.args.payment_flag,
+                t.args.timing,
             );
             assert_eq!(actual, t.expected, "args: {:#?}", t.args);
         }
@@ -428,40 +810,86 @@ mod tests_fv {
                     rate: 0.1,
                     nper: 12,
                     pmt: 10_000.0,
-                    pv: 0,
-                    payment_flag: false,
+                    pv: 0.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: -213_842.83767210032,
+                expected: Ok(-213_842.83767210032),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     nper: 12,
                     pmt: 10_000.0,
-                    pv: 0,
-                    payment_flag: true,
+                    pv: 0.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -235_227.12143931031,
+                expected: Ok(-235_227.12143931031),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     nper: 12,
                     pmt: 10_000.0,
-                    pv: 1_000,
-                    payment_flag: false,
+                    pv: 1_000.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: -216_981.26604882133,
+                expected: Ok(-216_981.26604882133),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     nper: 12,
                     pmt: 10_000.0,
-                    pv: 1_000,
-                    payment_flag: true,
+                    pv: 1_000.0,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(-238_365.54981603133),
+            },
+        ];
+        for t in &test_cases {
+            let actual = fv(
+                t.args.rate,
+                t.args.nper,
+                t.args.pmt,
+                t.args.pv,
+                t.args.timing,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_rate_is_minus_1() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    rate: -1.0,
+                    nper: -2,
+                    pmt: -100.0,
+                    pv: 100.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -1.0,
+                    nper: 0,
+                    pmt: -100.0,
+                    pv: 100.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -238_365.54981603133,
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -1.0,
+                    nper: 10,
+                    pmt: -100.0,
+                    pv: 100.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(100.0),
             },
         ];
         for t in &test_cases {
@@ -470,20 +898,32 @@ mod tests_fv {
                 t.args.nper,
                 t.args.pmt,
                 t.args.pv,
-                t.args.payment_flag,
+                t.args.timing,
             );
             assert_eq!(actual, t.expected, "args: {:#?}", t.args);
         }
     }
 }
 
-pub fn ppmt(rate: f64, per: i64, nper: i64, pv: i64, fv: i64, payment_flag: bool) -> f64 {
+pub fn ppmt(
+    rate: f64,
+    per: i64,
+    nper: i64,
+    pv: f64,
+    fv: f64,
+    timing: PaymentTiming,
+) -> Result<f64, XlsxError> {
     if per < 1 || per > nper {
-        return 0.0;
+        return Err(XlsxError::Num);
     }
-    let pmt = pmt(rate, nper, pv, fv, payment_flag);
-    let ipmt = ipmt(rate, per, nper, pv, fv, payment_flag);
-    pmt - ipmt
+    let pmt = pmt(rate, nper, pv, fv, timing)?;
+    let ipmt = ipmt(rate, per, nper, pv, fv, timing)?;
+    Ok(pmt - ipmt)
+}
+
+#[deprecated(since = "0.2.0", note = "use `ppmt` with f64 `pv` and `fv`")]
+pub fn ppmt_i64(rate: f64, per: i64, nper: i64, pv: i64, fv: i64, payment_flag: bool) -> f64 {
+    ppmt(rate, per, nper, pv as f64, fv as f64, payment_flag.into()).unwrap_or(0.0)
 }
 
 #[cfg(test)]
@@ -495,14 +935,14 @@ mod tests_ppmt {
         rate: f64,
         per: i64,
         nper: i64,
-        pv: i64,
-        fv: i64,
-        payment_flag: bool,
+        pv: f64,
+        fv: f64,
+        timing: PaymentTiming,
     }
 
     struct TestData {
         args: TestArgs,
-        expected: f64,
+        expected: Result<f64, XlsxError>,
     }
 
     #[test]
@@ -513,22 +953,22 @@ mod tests_ppmt {
                     rate: 0.1,
                     per: 0,
                     nper: 10,
-                    pv: 800_000,
-                    fv: 0,
-                    payment_flag: false,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: 0.0,
+                expected: Err(XlsxError::Num),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     per: -1,
                     nper: 10,
-                    pv: 800_000,
-                    fv: 0,
-                    payment_flag: false,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: 0.0,
+                expected: Err(XlsxError::Num),
             },
         ];
         for t in &test_cases {
@@ -538,7 +978,7 @@ mod tests_ppmt {
                 t.args.nper,
                 t.args.pv,
                 t.args.fv,
-                t.args.payment_flag,
+                t.args.timing,
             );
             assert_eq!(actual, t.expected, "args: {:#?}", t.args);
         }
@@ -552,22 +992,22 @@ mod tests_ppmt {
                     rate: 0.1,
                     per: 11,
                     nper: 10,
-                    pv: 800_000,
-                    fv: 0,
-                    payment_flag: false,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: 0.0,
+                expected: Err(XlsxError::Num),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     per: 15,
                     nper: 10,
-                    pv: 800_000,
-                    fv: 0,
-                    payment_flag: false,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: 0.0,
+                expected: Err(XlsxError::Num),
             },
         ];
         for t in &test_cases {
@@ -577,7 +1017,7 @@ mod tests_ppmt {
                 t.args.nper,
                 t.args.pv,
                 t.args.fv,
-                t.args.payment_flag,
+                t.args.timing,
             );
             assert_eq!(actual, t.expected, "args: {:#?}", t.args);
         }
@@ -591,44 +1031,44 @@ mod tests_ppmt {
                     rate: 0.1,
                     per: 12,
                     nper: 36,
-                    pv: 800_000,
-                    fv: 0,
-                    payment_flag: false,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: -7_630.520983834242,
+                expected: Ok(-7_630.520983834242),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     per: 12,
                     nper: 36,
-                    pv: 800_000,
-                    fv: 1_000,
-                    payment_flag: false,
+                    pv: 800_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::End,
                 },
-                expected: -7_640.059135064032,
+                expected: Ok(-7_640.059135064032),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     per: 12,
                     nper: 36,
-                    pv: 800_000,
-                    fv: 0,
-                    payment_flag: true,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -6_936.837258031126,
+                expected: Ok(-6_936.837258031126),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     per: 12,
                     nper: 36,
-                    pv: 800_000,
-                    fv: 1_000,
-                    payment_flag: true,
+                    pv: 800_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -6_945.50830460366,
+                expected: Ok(-6_945.50830460366),
             },
         ];
         for t in &test_cases {
@@ -638,84 +1078,172 @@ mod tests_ppmt {
                 t.args.nper,
                 t.args.pv,
                 t.args.fv,
-                t.args.payment_flag,
+                t.args.timing,
             );
             assert_eq!(actual, t.expected, "args: {:#?}", t.args);
         }
     }
-}
-
-pub fn cumipmt(rate: f64, nper: i64, pv: i64, start: i64, end: i64, payment_flag: bool) -> f64 {
-    if rate <= 0.0 || nper <= 0 || pv <= 0 {
-        return 0.0;
-    }
-
-    if start < 1 || end < 1 || start > end {
-        return 0.0;
-    }
-
-    let pmt = pmt(rate, nper, pv, 0, payment_flag);
-    let mut interest = 0.0;
-    let mut mut_start = start;
-    if start == 1 {
-        if !payment_flag {
-            interest = -pv as f64;
-        }
-        mut_start += 1;
-    }
-    for i in mut_start..end + 1 {
-        interest += if payment_flag {
-            fv(rate, i - 2, pmt, pv, true) - pmt
-        } else {
-            fv(rate, i - 1, pmt, pv, false)
-        };
-    }
-    interest * rate
-}
-
-#[cfg(test)]
-mod tests_cumipmt {
-    use super::*;
-
-    #[derive(Debug)]
-    struct TestArgs {
-        rate: f64,
-        nper: i64,
-        pv: i64,
-        start: i64,
-        end: i64,
-        payment_flag: bool,
-    }
-
-    struct TestData {
-        args: TestArgs,
-        expected: f64,
-    }
 
     #[test]
-    fn test_rate_le_0() {
+    fn test_first_period_paid_at_beginning() {
+        // Excel: =PPMT(10%/12, 1, 24, 2000, 0, 1) and =PPMT(10%, 1, 36, 800000, 0, 1)
+        // equal the whole payment, since no interest has accrued yet.
         let test_cases: [TestData; 2] = [
             TestData {
                 args: TestArgs {
-                    rate: 0.0,
-                    nper: 36,
-                    pv: 800_000,
-                    start: 6,
-                    end: 12,
-                    payment_flag: false,
+                    rate: 0.1 / 12.0,
+                    per: 1,
+                    nper: 24,
+                    pv: 2_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: 0.0,
+                expected: Ok(-91.52712661986776),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    per: 1,
+                    nper: 36,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(-75_158.59186790786),
+            },
+        ];
+        for t in &test_cases {
+            let actual = ppmt(
+                t.args.rate,
+                t.args.per,
+                t.args.nper,
+                t.args.pv,
+                t.args.fv,
+                t.args.timing,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+            let payment = pmt(
+                t.args.rate,
+                t.args.nper,
+                t.args.pv,
+                t.args.fv,
+                t.args.timing,
+            );
+            assert_eq!(actual, payment, "args: {:#?}", t.args);
+        }
+    }
+}
+
+pub fn cumipmt(
+    rate: f64,
+    nper: i64,
+    pv: f64,
+    start: i64,
+    end: i64,
+    timing: PaymentTiming,
+) -> Result<f64, XlsxError> {
+    if rate <= 0.0 || nper <= 0 || pv <= 0.0 {
+        return Err(XlsxError::Num);
+    }
+
+    if start < 1 || end < 1 || start > end || end > nper {
+        return Err(XlsxError::Num);
+    }
+
+    let pmt = pmt(rate, nper, pv, 0.0, timing)?;
+
+    // The interest of period k is charged on the balance left after period
+    // k - 1, so the balances from `first` to `last` form a geometric series.
+    let (first, balance) = match timing {
+        PaymentTiming::End => (start - 1, pv),
+        PaymentTiming::Beginning => (start.max(2) - 1, pv / (1.0 + rate)),
+    };
+    let last = end - 1;
+    if first > last {
+        return Ok(0.0);
+    }
+
+    let count = (last - first + 1) as f64;
+    let growth = rate.ln_1p();
+    let series = ((first as f64) * growth).exp() * (count * growth).exp_m1() / rate;
+    Ok(-(rate * balance * series + pmt * (series - count)))
+}
+
+#[deprecated(since = "0.2.0", note = "use `cumipmt` with f64 `pv`")]
+pub fn cumipmt_i64(rate: f64, nper: i64, pv: i64, start: i64, end: i64, payment_flag: bool) -> f64 {
+    cumipmt(rate, nper, pv as f64, start, end, payment_flag.into()).unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests_cumipmt {
+    use super::*;
+
+    fn cumipmt_by_loop(
+        rate: f64,
+        nper: i64,
+        pv: f64,
+        start: i64,
+        end: i64,
+        timing: PaymentTiming,
+    ) -> f64 {
+        let pmt = pmt(rate, nper, pv, 0.0, timing).unwrap();
+        let mut interest = 0.0;
+        let mut mut_start = start;
+        if start == 1 {
+            if timing == PaymentTiming::End {
+                interest = -pv;
+            }
+            mut_start += 1;
+        }
+        for i in mut_start..end + 1 {
+            interest += if timing == PaymentTiming::Beginning {
+                fv(rate, i - 2, pmt, pv, PaymentTiming::Beginning).unwrap() - pmt
+            } else {
+                fv(rate, i - 1, pmt, pv, PaymentTiming::End).unwrap()
+            };
+        }
+        interest * rate
+    }
+
+    #[derive(Debug)]
+    struct TestArgs {
+        rate: f64,
+        nper: i64,
+        pv: f64,
+        start: i64,
+        end: i64,
+        timing: PaymentTiming,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_rate_le_0() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.0,
+                    nper: 36,
+                    pv: 800_000.0,
+                    start: 6,
+                    end: 12,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
             },
             TestData {
                 args: TestArgs {
                     rate: -1.0,
                     nper: 36,
-                    pv: 800_000,
+                    pv: 800_000.0,
                     start: 6,
                     end: 12,
-                    payment_flag: false,
+                    timing: PaymentTiming::End,
                 },
-                expected: 0.0,
+                expected: Err(XlsxError::Num),
             },
         ];
         for t in &test_cases {
@@ -725,7 +1253,7 @@ mod tests_cumipmt {
                 t.args.pv,
                 t.args.start,
                 t.args.end,
-                t.args.payment_flag,
+                t.args.timing,
             );
             assert_eq!(actual, t.expected, "args: {:#?}", t.args);
         }
@@ -738,23 +1266,23 @@ mod tests_cumipmt {
                 args: TestArgs {
                     rate: 0.1,
                     nper: 0,
-                    pv: 800_000,
+                    pv: 800_000.0,
                     start: 6,
                     end: 12,
-                    payment_flag: false,
+                    timing: PaymentTiming::End,
                 },
-                expected: 0.0,
+                expected: Err(XlsxError::Num),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     nper: -1,
-                    pv: 800_000,
+                    pv: 800_000.0,
                     start: 6,
                     end: 12,
-                    payment_flag: false,
+                    timing: PaymentTiming::End,
                 },
-                expected: 0.0,
+                expected: Err(XlsxError::Num),
             },
         ];
         for t in &test_cases {
@@ -764,7 +1292,7 @@ mod tests_cumipmt {
                 t.args.pv,
                 t.args.start,
                 t.args.end,
-                t.args.payment_flag,
+                t.args.timing,
             );
             assert_eq!(actual, t.expected, "args: {:#?}", t.args);
         }
@@ -777,23 +1305,23 @@ mod tests_cumipmt {
                 args: TestArgs {
                     rate: 0.1,
                     nper: 36,
-                    pv: 0,
+                    pv: 0.0,
                     start: 6,
                     end: 12,
-                    payment_flag: false,
+                    timing: PaymentTiming::End,
                 },
-                expected: 0.0,
+                expected: Err(XlsxError::Num),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     nper: 36,
-                    pv: -1,
+                    pv: -1.0,
                     start: 6,
                     end: 12,
-                    payment_flag: false,
+                    timing: PaymentTiming::End,
                 },
-                expected: 0.0,
+                expected: Err(XlsxError::Num),
             },
         ];
         for t in &test_cases {
@@ -803,7 +1331,7 @@ mod tests_cumipmt {
                 t.args.pv,
                 t.args.start,
                 t.args.end,
-                t.args.payment_flag,
+                t.args.timing,
             );
             assert_eq!(actual, t.expected, "args: {:#?}", t.args);
         }
@@ -811,39 +1339,61 @@ mod tests_cumipmt {
 
     #[test]
     fn test_start_or_end_is_an_invalid_value() {
-        let test_cases: [TestData; 3] = [
+        let test_cases: [TestData; 5] = [
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     nper: 36,
-                    pv: 800_000,
+                    pv: 800_000.0,
                     start: 0,
                     end: 12,
-                    payment_flag: false,
+                    timing: PaymentTiming::End,
                 },
-                expected: 0.0,
+                expected: Err(XlsxError::Num),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     nper: 36,
-                    pv: 800_000,
+                    pv: 800_000.0,
                     start: 1,
                     end: 0,
-                    payment_flag: false,
+                    timing: PaymentTiming::End,
                 },
-                expected: 0.0,
+                expected: Err(XlsxError::Num),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     nper: 36,
-                    pv: 800_000,
+                    pv: 800_000.0,
                     start: 10,
                     end: 9,
-                    payment_flag: false,
+                    timing: PaymentTiming::End,
                 },
-                expected: 0.0,
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 36,
+                    pv: 800_000.0,
+                    start: 1,
+                    end: 37,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.01,
+                    nper: 10,
+                    pv: 1_000.0,
+                    start: 1,
+                    end: 20,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Err(XlsxError::Num),
             },
         ];
         for t in &test_cases {
@@ -853,7 +1403,7 @@ mod tests_cumipmt {
                 t.args.pv,
                 t.args.start,
                 t.args.end,
-                t.args.payment_flag,
+                t.args.timing,
             );
             assert_eq!(actual, t.expected, "args: {:#?}", t.args);
         }
@@ -866,45 +1416,91 @@ mod tests_cumipmt {
                 args: TestArgs {
                     rate: 0.1,
                     nper: 36,
-                    pv: 800_000,
+                    pv: 800_000.0,
                     start: 6,
                     end: 12,
-                    payment_flag: true,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -488_961.5711288557,
+                expected: Ok(-488_961.5711288557),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     nper: 36,
-                    pv: 800_000,
+                    pv: 800_000.0,
                     start: 6,
                     end: 12,
-                    payment_flag: false,
+                    timing: PaymentTiming::End,
                 },
-                expected: -537_857.7282417413,
+                expected: Ok(-537_857.7282417413),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     nper: 36,
-                    pv: 800_000,
+                    pv: 800_000.0,
                     start: 1,
                     end: 12,
-                    payment_flag: true,
+                    timing: PaymentTiming::Beginning,
                 },
-                expected: -777_183.8112556307,
+                expected: Ok(-777_183.8112556307),
             },
             TestData {
                 args: TestArgs {
                     rate: 0.1,
                     nper: 36,
-                    pv: 800_000,
+                    pv: 800_000.0,
                     start: 1,
                     end: 12,
-                    payment_flag: false,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(-934_902.1923811939),
+            },
+        ];
+        for t in &test_cases {
+            let actual = cumipmt(
+                t.args.rate,
+                t.args.nper,
+                t.args.pv,
+                t.args.start,
+                t.args.end,
+                t.args.timing,
+            )
+            .unwrap();
+            let expected = t.expected.unwrap();
+            assert!(
+                (actual - expected).abs() <= 1e-12 * expected.abs(),
+                "args: {:#?}, actual: {}",
+                t.args,
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn test_excel_examples() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.09 / 12.0,
+                    nper: 360,
+                    pv: 125_000.0,
+                    start: 13,
+                    end: 24,
+                    timing: PaymentTiming::End,
                 },
-                expected: -934_902.1923811939,
+                expected: Ok(-11_135.232130750845),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.09 / 12.0,
+                    nper: 360,
+                    pv: 125_000.0,
+                    start: 1,
+                    end: 1,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(-937.5),
             },
         ];
         for t in &test_cases {
@@ -914,9 +1510,7336 @@ mod tests_cumipmt {
                 t.args.pv,
                 t.args.start,
                 t.args.end,
-                t.args.payment_flag,
+                t.args.timing,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_first_period_paid_at_beginning() {
+        let actual = cumipmt(0.1, 36, 800_000.0, 1, 1, PaymentTiming::Beginning);
+        assert_eq!(actual, Ok(0.0));
+    }
+
+    #[test]
+    fn test_agree_with_loop() {
+        let loans: [(f64, i64, f64); 3] = [
+            (0.1, 36, 800_000.0),
+            (0.09 / 12.0, 360, 125_000.0),
+            (0.05 / 365.0, 10_950, 300_000.0),
+        ];
+        for &(rate, nper, pv) in &loans {
+            for &timing in &[PaymentTiming::End, PaymentTiming::Beginning] {
+                for &(start, end) in &[(1, 1), (1, 2), (2, 2), (1, nper), (nper / 3, nper / 2)] {
+                    let actual = cumipmt(rate, nper, pv, start, end, timing).unwrap();
+                    let expected = cumipmt_by_loop(rate, nper, pv, start, end, timing);
+                    assert!(
+                        (actual - expected).abs() <= 1e-12 * pv * (end - start + 1) as f64,
+                        "rate: {}, nper: {}, start: {}, end: {}, timing: {:?}, actual: {}, expected: {}",
+                        rate,
+                        nper,
+                        start,
+                        end,
+                        timing,
+                        actual,
+                        expected
+                    );
+                }
+            }
+        }
+    }
+}
+
+pub fn pv(
+    rate: f64,
+    nper: i64,
+    pmt: f64,
+    fv: f64,
+    timing: PaymentTiming,
+) -> Result<f64, XlsxError> {
+    let nper_f64 = nper as f64;
+
+    if rate == 0.0 {
+        return Ok(-(fv + pmt * nper_f64));
+    }
+
+    if rate == -1.0 {
+        return Err(XlsxError::Num);
+    }
+
+    let term = (1.0 + rate).powf(nper_f64);
+    if timing == PaymentTiming::Beginning {
+        return Ok(-(fv + (pmt * (1.0 + rate) * (term - 1.0)) / rate) / term);
+    }
+    Ok(-(fv + (pmt * (term - 1.0)) / rate) / term)
+}
+
+#[cfg(test)]
+mod tests_pv {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        rate: f64,
+        nper: i64,
+        pmt: f64,
+        fv: f64,
+        timing: PaymentTiming,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_rate_is_0() {
+        let test_cases: [TestData; 4] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.0,
+                    nper: 12,
+                    pmt: -10_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(120_000.0),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.0,
+                    nper: 12,
+                    pmt: -10_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(120_000.0),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.0,
+                    nper: 12,
+                    pmt: -10_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(119_000.0),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.0,
+                    nper: 12,
+                    pmt: -10_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(119_000.0),
+            },
+        ];
+        for t in &test_cases {
+            let actual = pv(
+                t.args.rate,
+                t.args.nper,
+                t.args.pmt,
+                t.args.fv,
+                t.args.timing,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_rate_is_over_0() {
+        let test_cases: [TestData; 5] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 12,
+                    pmt: -10_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(68_136.91822896435),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 12,
+                    pmt: -10_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(74_950.61005186077),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 12,
+                    pmt: -10_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(67_818.287411254),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 12,
+                    pmt: -10_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(74_631.97923415042),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.08 / 12.0,
+                    nper: 240,
+                    pmt: 500.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(-59_777.14585118777),
+            },
+        ];
+        for t in &test_cases {
+            let actual = pv(
+                t.args.rate,
+                t.args.nper,
+                t.args.pmt,
+                t.args.fv,
+                t.args.timing,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_rate_is_minus_1() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    rate: -1.0,
+                    nper: 10,
+                    pmt: -100.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -1.0,
+                    nper: 10,
+                    pmt: -100.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = pv(
+                t.args.rate,
+                t.args.nper,
+                t.args.pmt,
+                t.args.fv,
+                t.args.timing,
             );
             assert_eq!(actual, t.expected, "args: {:#?}", t.args);
         }
     }
 }
+
+pub fn nper(
+    rate: f64,
+    pmt: f64,
+    pv: f64,
+    fv: f64,
+    timing: PaymentTiming,
+) -> Result<f64, XlsxError> {
+    if rate == 0.0 {
+        if pmt == 0.0 {
+            return Err(XlsxError::Num);
+        }
+        return Ok(-(pv + fv) / pmt);
+    }
+
+    if rate <= -1.0 {
+        return Err(XlsxError::Num);
+    }
+
+    let pmt_adjusted = if timing == PaymentTiming::Beginning {
+        pmt * (1.0 + rate)
+    } else {
+        pmt
+    };
+    let ratio = (pmt_adjusted - fv * rate) / (pmt_adjusted + pv * rate);
+    if !ratio.is_finite() || ratio <= 0.0 {
+        return Err(XlsxError::Num);
+    }
+    Ok(ratio.ln() / (1.0 + rate).ln())
+}
+
+#[cfg(test)]
+mod tests_nper {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        rate: f64,
+        pmt: f64,
+        pv: f64,
+        fv: f64,
+        timing: PaymentTiming,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_rate_is_0() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.0,
+                    pmt: -100.0,
+                    pv: 1_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(10.0),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.0,
+                    pmt: -100.0,
+                    pv: 1_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(20.0),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.0,
+                    pmt: 0.0,
+                    pv: 1_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = nper(t.args.rate, t.args.pmt, t.args.pv, t.args.fv, t.args.timing);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_rate_is_over_0() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.12 / 12.0,
+                    pmt: -100.0,
+                    pv: -1_000.0,
+                    fv: 10_000.0,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(59.67386567429457),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.12 / 12.0,
+                    pmt: -100.0,
+                    pv: -1_000.0,
+                    fv: 10_000.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(60.08212285376166),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.12 / 12.0,
+                    pmt: -100.0,
+                    pv: -1_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(-9.578594039813161),
+            },
+        ];
+        for t in &test_cases {
+            let actual = nper(t.args.rate, t.args.pmt, t.args.pv, t.args.fv, t.args.timing);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_pmt_does_not_cover_interest() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    pmt: -50.0,
+                    pv: 1_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    pmt: -100.0,
+                    pv: 1_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -1.0,
+                    pmt: -100.0,
+                    pv: 1_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = nper(t.args.rate, t.args.pmt, t.args.pv, t.args.fv, t.args.timing);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_with_pmt_and_fv() {
+        for &timing in &[PaymentTiming::End, PaymentTiming::Beginning] {
+            let payment = pmt(0.1, 36, 800_000.0, 1_000.0, timing).unwrap();
+            let actual = nper(0.1, payment, 800_000.0, 1_000.0, timing).unwrap();
+            assert!((actual - 36.0).abs() < 1e-9, "actual: {}", actual);
+
+            let actual = fv(0.1, actual.round() as i64, payment, 800_000.0, timing).unwrap();
+            assert!((actual - 1_000.0).abs() < 1e-6, "actual: {}", actual);
+        }
+    }
+}
+
+const RATE_MAX_ITERATIONS: usize = 20;
+const RATE_TOLERANCE: f64 = 1e-7;
+
+fn newton<F>(f: F, guess: f64, max_iterations: usize, tolerance: f64) -> Option<f64>
+where
+    F: Fn(f64) -> (f64, f64),
+{
+    let mut x = guess;
+    for _ in 0..max_iterations {
+        let (y, dy) = f(x);
+        if !y.is_finite() || !dy.is_finite() || dy == 0.0 {
+            return None;
+        }
+        let next = x - y / dy;
+        if (next - x).abs() < tolerance {
+            return Some(next);
+        }
+        x = next;
+    }
+    None
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateOptions {
+    pub guess: f64,
+    pub max_iterations: usize,
+    pub tolerance: f64,
+}
+
+impl Default for RateOptions {
+    fn default() -> Self {
+        RateOptions {
+            guess: 0.1,
+            max_iterations: RATE_MAX_ITERATIONS,
+            tolerance: RATE_TOLERANCE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RatePhase {
+    /// Newton's method converged within `max_iterations` steps.
+    Newton,
+    /// Newton's method failed and the root was found by scanning for a sign
+    /// change and bisecting it.
+    Bracketed,
+}
+
+pub fn rate(
+    nper: i64,
+    pmt: f64,
+    pv: f64,
+    fv: f64,
+    timing: PaymentTiming,
+    guess: f64,
+) -> Result<f64, XlsxError> {
+    let options = RateOptions {
+        guess,
+        ..RateOptions::default()
+    };
+    rate_with(nper, pmt, pv, fv, timing, options).map(|(rate, _)| rate)
+}
+
+pub fn rate_with(
+    nper: i64,
+    pmt: f64,
+    pv: f64,
+    fv: f64,
+    timing: PaymentTiming,
+    options: RateOptions,
+) -> Result<(f64, RatePhase), XlsxError> {
+    if nper <= 0 {
+        return Err(XlsxError::Num);
+    }
+
+    let nper_f64 = nper as f64;
+    let payment_type = match timing {
+        PaymentTiming::End => 0.0,
+        PaymentTiming::Beginning => 1.0,
+    };
+
+    let annuity = |r: f64| {
+        if r == 0.0 {
+            let y = pv + pmt * nper_f64 + fv;
+            let dy = pmt * nper_f64 * (payment_type - (nper_f64 + 1.0) / 2.0) - fv * nper_f64;
+            return (y, dy);
+        }
+        let discount = (1.0 + r).powf(-nper_f64);
+        let ddiscount = -nper_f64 * (1.0 + r).powf(-nper_f64 - 1.0);
+        let annuity_factor = (1.0 - discount) / r;
+        let dannuity_factor = (-ddiscount * r - (1.0 - discount)) / (r * r);
+        let y = pv + pmt * (1.0 + r * payment_type) * annuity_factor + fv * discount;
+        let dy = pmt * (payment_type * annuity_factor + (1.0 + r * payment_type) * dannuity_factor)
+            + fv * ddiscount;
+        (y, dy)
+    };
+
+    let newton_phase = newton(
+        annuity,
+        options.guess,
+        options.max_iterations,
+        options.tolerance,
+    );
+    match newton_phase {
+        Some(rate) if rate > -1.0 => Ok((rate, RatePhase::Newton)),
+        _ => {
+            let bracketed_phase = nearest_root(scan_roots(|r| annuity(r).0), options.guess);
+            bracketed_phase.map(|rate| (rate, RatePhase::Bracketed))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_rate {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        nper: i64,
+        pmt: f64,
+        pv: f64,
+        fv: f64,
+        timing: PaymentTiming,
+        guess: f64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_nper_le_0() {
+        let actual = rate(0, -200.0, 8_000.0, 0.0, PaymentTiming::End, 0.1);
+        assert_eq!(actual, Err(XlsxError::Num));
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 7] = [
+            TestData {
+                args: TestArgs {
+                    nper: 48,
+                    pmt: -200.0,
+                    pv: 8_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                    guess: 0.1,
+                },
+                expected: Ok(0.007701472488201827),
+            },
+            TestData {
+                args: TestArgs {
+                    nper: 48,
+                    pmt: -200.0,
+                    pv: 8_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::Beginning,
+                    guess: 0.1,
+                },
+                expected: Ok(0.008052981923904011),
+            },
+            TestData {
+                args: TestArgs {
+                    nper: 36,
+                    pmt: -30_002.396162618596,
+                    pv: 100_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::End,
+                    guess: 0.1,
+                },
+                expected: Ok(0.30000000000000004),
+            },
+            TestData {
+                args: TestArgs {
+                    nper: 36,
+                    pmt: -23_078.76627893738,
+                    pv: 100_000.0,
+                    fv: 1_000.0,
+                    timing: PaymentTiming::Beginning,
+                    guess: 0.1,
+                },
+                expected: Ok(0.29999999999999993),
+            },
+            TestData {
+                args: TestArgs {
+                    nper: 60,
+                    pmt: -1_887.12,
+                    pv: 100_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                    guess: 0.1,
+                },
+                expected: Ok(0.0041666054704238955),
+            },
+            TestData {
+                args: TestArgs {
+                    nper: 120,
+                    pmt: -1_060.66,
+                    pv: 100_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                    guess: 0.1,
+                },
+                expected: Ok(0.0041667493124384735),
+            },
+            TestData {
+                args: TestArgs {
+                    nper: 360,
+                    pmt: -1_199.10,
+                    pv: 200_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                    guess: 0.1,
+                },
+                expected: Ok(0.004999993193119239),
+            },
+        ];
+        for t in &test_cases {
+            let actual = rate(
+                t.args.nper,
+                t.args.pmt,
+                t.args.pv,
+                t.args.fv,
+                t.args.timing,
+                t.args.guess,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_rate_is_0() {
+        let actual = rate(36, -100.0, 3_600.0, 0.0, PaymentTiming::End, 0.1).unwrap();
+        assert!(actual.abs() < RATE_TOLERANCE, "actual: {}", actual);
+    }
+
+    #[test]
+    fn test_not_converge() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    nper: 36,
+                    pmt: 1_000.0,
+                    pv: 800_000.0,
+                    fv: 0.0,
+                    timing: PaymentTiming::End,
+                    guess: 0.1,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    nper: 36,
+                    pmt: -1_000.0,
+                    pv: -800_000.0,
+                    fv: -1_000.0,
+                    timing: PaymentTiming::Beginning,
+                    guess: 0.1,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = rate(
+                t.args.nper,
+                t.args.pmt,
+                t.args.pv,
+                t.args.fv,
+                t.args.timing,
+                t.args.guess,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_default_options() {
+        let options = RateOptions::default();
+        assert_eq!(options.guess, 0.1);
+        assert_eq!(options.max_iterations, 20);
+        assert_eq!(options.tolerance, 1e-7);
+    }
+
+    #[test]
+    fn test_rate_with() {
+        let default = RateOptions::default();
+        let test_cases: [(i64, f64, f64, RateOptions, f64, RatePhase); 6] = [
+            (
+                48,
+                -200.0,
+                8_000.0,
+                default,
+                0.007701472488201827,
+                RatePhase::Newton,
+            ),
+            (
+                48,
+                -200.0,
+                8_000.0,
+                RateOptions {
+                    max_iterations: 1,
+                    ..default
+                },
+                0.007701472488202255,
+                RatePhase::Bracketed,
+            ),
+            (
+                48,
+                -200.0,
+                8_000.0,
+                RateOptions {
+                    tolerance: 1e-3,
+                    ..default
+                },
+                0.007696548577223561,
+                RatePhase::Newton,
+            ),
+            (
+                60,
+                -1_887.12,
+                100_000.0,
+                default,
+                0.0041666054704238955,
+                RatePhase::Bracketed,
+            ),
+            (
+                60,
+                -1_887.12,
+                100_000.0,
+                RateOptions {
+                    max_iterations: 100,
+                    ..default
+                },
+                0.004166605470399673,
+                RatePhase::Newton,
+            ),
+            (
+                360,
+                -1_199.10,
+                200_000.0,
+                RateOptions {
+                    guess: 0.01,
+                    ..default
+                },
+                0.004999993193119184,
+                RatePhase::Newton,
+            ),
+        ];
+        for &(nper, pmt, pv, options, expected_rate, expected_phase) in &test_cases {
+            let actual = rate_with(nper, pmt, pv, 0.0, PaymentTiming::End, options);
+            assert_eq!(
+                actual,
+                Ok((expected_rate, expected_phase)),
+                "nper: {}, options: {:?}",
+                nper,
+                options
+            );
+        }
+    }
+}
+
+pub fn cumprinc(
+    rate: f64,
+    nper: i64,
+    pv: f64,
+    start: i64,
+    end: i64,
+    timing: PaymentTiming,
+) -> Result<f64, XlsxError> {
+    if rate <= 0.0 || nper <= 0 || pv <= 0.0 {
+        return Err(XlsxError::Num);
+    }
+
+    if start < 1 || end < 1 || start > end || end > nper {
+        return Err(XlsxError::Num);
+    }
+
+    let pmt = pmt(rate, nper, pv, 0.0, timing)?;
+    if timing == PaymentTiming::End {
+        return Ok(fv(rate, start - 1, pmt, pv, PaymentTiming::End)?
+            - fv(rate, end, pmt, pv, PaymentTiming::End)?);
+    }
+
+    let balance_before_start = if start == 1 {
+        pv
+    } else {
+        -fv(rate, start - 1, pmt, pv, PaymentTiming::Beginning)? / (1.0 + rate)
+    };
+    let balance_after_end = -fv(rate, end, pmt, pv, PaymentTiming::Beginning)? / (1.0 + rate);
+    Ok(balance_after_end - balance_before_start)
+}
+
+#[cfg(test)]
+mod tests_cumprinc {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        rate: f64,
+        nper: i64,
+        pv: f64,
+        start: i64,
+        end: i64,
+        timing: PaymentTiming,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_rate_le_0() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.0,
+                    nper: 36,
+                    pv: 800_000.0,
+                    start: 6,
+                    end: 12,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -1.0,
+                    nper: 36,
+                    pv: 800_000.0,
+                    start: 6,
+                    end: 12,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = cumprinc(
+                t.args.rate,
+                t.args.nper,
+                t.args.pv,
+                t.args.start,
+                t.args.end,
+                t.args.timing,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_nper_le_0() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 0,
+                    pv: 800_000.0,
+                    start: 6,
+                    end: 12,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: -1,
+                    pv: 800_000.0,
+                    start: 6,
+                    end: 12,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = cumprinc(
+                t.args.rate,
+                t.args.nper,
+                t.args.pv,
+                t.args.start,
+                t.args.end,
+                t.args.timing,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_pv_le_0() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 36,
+                    pv: 0.0,
+                    start: 6,
+                    end: 12,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 36,
+                    pv: -1.0,
+                    start: 6,
+                    end: 12,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = cumprinc(
+                t.args.rate,
+                t.args.nper,
+                t.args.pv,
+                t.args.start,
+                t.args.end,
+                t.args.timing,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_start_or_end_is_an_invalid_value() {
+        let test_cases: [TestData; 5] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 36,
+                    pv: 800_000.0,
+                    start: 0,
+                    end: 12,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 36,
+                    pv: 800_000.0,
+                    start: 1,
+                    end: 0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 36,
+                    pv: 800_000.0,
+                    start: 10,
+                    end: 9,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 36,
+                    pv: 800_000.0,
+                    start: 1,
+                    end: 37,
+                    timing: PaymentTiming::End,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.01,
+                    nper: 10,
+                    pv: 1_000.0,
+                    start: 1,
+                    end: 20,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = cumprinc(
+                t.args.rate,
+                t.args.nper,
+                t.args.pv,
+                t.args.start,
+                t.args.end,
+                t.args.timing,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 4] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 36,
+                    pv: 800_000.0,
+                    start: 6,
+                    end: 12,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(-37_148.57194649964),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 36,
+                    pv: 800_000.0,
+                    start: 6,
+                    end: 12,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(-40_863.42914114962),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 36,
+                    pv: 800_000.0,
+                    start: 1,
+                    end: 12,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(-124_719.29115926404),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    nper: 36,
+                    pv: 800_000.0,
+                    start: 1,
+                    end: 12,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(-57_191.220275190426),
+            },
+        ];
+        for t in &test_cases {
+            let actual = cumprinc(
+                t.args.rate,
+                t.args.nper,
+                t.args.pv,
+                t.args.start,
+                t.args.end,
+                t.args.timing,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_excel_examples() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.09 / 12.0,
+                    nper: 360,
+                    pv: 125_000.0,
+                    start: 13,
+                    end: 24,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(-934.1071234208794),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.09 / 12.0,
+                    nper: 360,
+                    pv: 125_000.0,
+                    start: 1,
+                    end: 1,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(-68.27827118097048),
+            },
+        ];
+        for t in &test_cases {
+            let actual = cumprinc(
+                t.args.rate,
+                t.args.nper,
+                t.args.pv,
+                t.args.start,
+                t.args.end,
+                t.args.timing,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_cross_check_with_cumipmt() {
+        for &timing in &[PaymentTiming::End, PaymentTiming::Beginning] {
+            for &(start, end) in &[(1, 1), (1, 12), (6, 12), (13, 36)] {
+                let payment = pmt(0.1, 36, 800_000.0, 0.0, timing).unwrap();
+                let interest = cumipmt(0.1, 36, 800_000.0, start, end, timing).unwrap();
+                let principal = cumprinc(0.1, 36, 800_000.0, start, end, timing).unwrap();
+                let expected = payment * (end - start + 1) as f64;
+                assert!(
+                    (interest + principal - expected).abs() < 1e-6,
+                    "start: {}, end: {}, timing: {:?}",
+                    start,
+                    end,
+                    timing
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(deprecated)]
+mod tests_deprecated_i64 {
+    use super::*;
+
+    #[test]
+    fn test_same_as_f64() {
+        for &payment_flag in &[false, true] {
+            assert_eq!(
+                pmt_i64(0.1, 36, 800_000, 1_000, payment_flag),
+                pmt(0.1, 36, 800_000.0, 1_000.0, payment_flag.into()).unwrap()
+            );
+            assert_eq!(
+                ipmt_i64(0.1, 2, 36, 800_000, 1_000, payment_flag),
+                ipmt(0.1, 2, 36, 800_000.0, 1_000.0, payment_flag.into()).unwrap()
+            );
+            assert_eq!(
+                fv_i64(0.1, 12, 10_000.0, 1_000, payment_flag),
+                fv(0.1, 12, 10_000.0, 1_000.0, payment_flag.into()).unwrap()
+            );
+            assert_eq!(
+                ppmt_i64(0.1, 12, 36, 800_000, 1_000, payment_flag),
+                ppmt(0.1, 12, 36, 800_000.0, 1_000.0, payment_flag.into()).unwrap()
+            );
+            assert_eq!(
+                cumipmt_i64(0.1, 36, 800_000, 6, 12, payment_flag),
+                cumipmt(0.1, 36, 800_000.0, 6, 12, payment_flag.into()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_error_is_0() {
+        assert_eq!(pmt_i64(0.1, 0, 800_000, 0, false), 0.0);
+        assert_eq!(ipmt_i64(0.1, 0, 36, 800_000, 0, false), 0.0);
+        assert_eq!(ppmt_i64(0.1, 37, 36, 800_000, 0, false), 0.0);
+        assert_eq!(cumipmt_i64(0.0, 36, 800_000, 6, 12, false), 0.0);
+        assert_eq!(fv_i64(-1.0, -2, -100.0, 100, false), 0.0);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmortizationRow {
+    pub period: i64,
+    pub payment: f64,
+    pub interest: f64,
+    pub principal: f64,
+    pub opening_balance: f64,
+    pub closing_balance: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AmortizationSchedule {
+    rate: f64,
+    nper: i64,
+    timing: PaymentTiming,
+    payment: f64,
+    period: i64,
+    balance: f64,
+}
+
+impl AmortizationSchedule {
+    pub fn new(
+        rate: f64,
+        nper: i64,
+        pv: f64,
+        fv: f64,
+        timing: PaymentTiming,
+    ) -> Result<Self, XlsxError> {
+        if nper < 1 {
+            return Err(XlsxError::Num);
+        }
+
+        let payment = pmt(rate, nper, pv, fv, timing)?;
+        Ok(AmortizationSchedule {
+            rate,
+            nper,
+            timing,
+            payment,
+            period: 0,
+            balance: pv,
+        })
+    }
+}
+
+impl Iterator for AmortizationSchedule {
+    type Item = AmortizationRow;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.period >= self.nper {
+            return None;
+        }
+        self.period += 1;
+
+        let opening_balance = self.balance;
+        let interest = if self.period == 1 && self.timing == PaymentTiming::Beginning {
+            0.0
+        } else {
+            -opening_balance * self.rate
+        };
+        let principal = self.payment - interest;
+        self.balance = opening_balance + principal;
+
+        Some(AmortizationRow {
+            period: self.period,
+            payment: self.payment,
+            interest,
+            principal,
+            opening_balance,
+            closing_balance: self.balance,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.nper - self.period) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for AmortizationSchedule {}
+
+#[cfg(test)]
+mod tests_amortization_schedule {
+    use super::*;
+
+    #[test]
+    fn test_nper_lt_1() {
+        let actual = AmortizationSchedule::new(0.1, 0, 1_000.0, 0.0, PaymentTiming::End);
+        assert_eq!(actual.unwrap_err(), XlsxError::Num);
+    }
+
+    #[test]
+    fn test_rows() {
+        let expected: [AmortizationRow; 3] = [
+            AmortizationRow {
+                period: 1,
+                payment: -402.1148036253773,
+                interest: -100.0,
+                principal: -302.1148036253773,
+                opening_balance: 1_000.0,
+                closing_balance: 697.8851963746226,
+            },
+            AmortizationRow {
+                period: 2,
+                payment: -402.1148036253773,
+                interest: -69.78851963746227,
+                principal: -332.326283987915,
+                opening_balance: 697.8851963746226,
+                closing_balance: 365.5589123867076,
+            },
+            AmortizationRow {
+                period: 3,
+                payment: -402.1148036253773,
+                interest: -36.55589123867076,
+                principal: -365.55891238670654,
+                opening_balance: 365.5589123867076,
+                closing_balance: 1.0800249583553523e-12,
+            },
+        ];
+        let actual: Vec<AmortizationRow> =
+            AmortizationSchedule::new(0.1, 3, 1_000.0, 0.0, PaymentTiming::End)
+                .unwrap()
+                .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_len() {
+        let mut schedule =
+            AmortizationSchedule::new(0.1, 36, 800_000.0, 0.0, PaymentTiming::End).unwrap();
+        assert_eq!(schedule.len(), 36);
+        schedule.next();
+        assert_eq!(schedule.len(), 35);
+    }
+
+    #[test]
+    fn test_agree_with_ipmt_and_ppmt() {
+        for &timing in &[PaymentTiming::End, PaymentTiming::Beginning] {
+            for &fv in &[0.0, 1_000.0] {
+                let schedule = AmortizationSchedule::new(0.1, 36, 800_000.0, fv, timing).unwrap();
+                for row in schedule {
+                    let interest = ipmt(0.1, row.period, 36, 800_000.0, fv, timing).unwrap();
+                    let principal = ppmt(0.1, row.period, 36, 800_000.0, fv, timing).unwrap();
+                    assert!(
+                        (row.interest - interest).abs() < 1e-9,
+                        "row: {:#?}, ipmt: {}",
+                        row,
+                        interest
+                    );
+                    assert!(
+                        (row.principal - principal).abs() < 1e-9,
+                        "row: {:#?}, ppmt: {}",
+                        row,
+                        principal
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_closing_balance_is_fv() {
+        let test_cases: [(PaymentTiming, f64); 2] = [
+            (PaymentTiming::End, -1_000.0),
+            (PaymentTiming::Beginning, -1_000.0 / 1.1),
+        ];
+        for &(timing, expected) in &test_cases {
+            let last = AmortizationSchedule::new(0.1, 36, 800_000.0, 1_000.0, timing)
+                .unwrap()
+                .last()
+                .unwrap();
+            assert!(
+                (last.closing_balance - expected).abs() < 1e-6,
+                "last: {:#?}",
+                last
+            );
+        }
+    }
+}
+
+pub fn npv(rate: f64, values: &[f64]) -> Result<f64, XlsxError> {
+    if rate == -1.0 {
+        return Err(XlsxError::Div0);
+    }
+
+    Ok(values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| value / (1.0 + rate).powf((i + 1) as f64))
+        .sum())
+}
+
+#[cfg(test)]
+mod tests_npv {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        rate: f64,
+        values: &'static [f64],
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_rate_is_minus_1() {
+        let actual = npv(-1.0, &[-10_000.0, 3_000.0, 4_200.0, 6_800.0]);
+        assert_eq!(actual, Err(XlsxError::Div0));
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 6] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    values: &[-10_000.0, 3_000.0, 4_200.0, 6_800.0],
+                },
+                expected: Ok(1_188.4434123352216),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.08,
+                    values: &[8_000.0, 9_200.0, 10_000.0, 12_000.0, 14_500.0],
+                },
+                expected: Ok(41_922.06155493236),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.08,
+                    values: &[8_000.0, 9_200.0, 10_000.0, 12_000.0, 14_500.0, -9_000.0],
+                },
+                expected: Ok(36_250.534912984425),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.0,
+                    values: &[8_000.0, 9_200.0],
+                },
+                expected: Ok(17_200.0),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -0.5,
+                    values: &[100.0, 100.0],
+                },
+                expected: Ok(600.0),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    values: &[],
+                },
+                expected: Ok(0.0),
+            },
+        ];
+        for t in &test_cases {
+            let actual = npv(t.args.rate, t.args.values);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+}
+
+const IRR_MAX_ITERATIONS: usize = 20;
+const IRR_TOLERANCE: f64 = 1e-7;
+const ROOT_SCAN_RANGE: f64 = 5.0;
+const ROOT_SCAN_STEPS: usize = 1_000;
+
+fn bisection<F>(f: F, mut low: f64, mut high: f64) -> Option<f64>
+where
+    F: Fn(f64) -> f64,
+{
+    let mut y_low = f(low);
+    let y_high = f(high);
+    if y_low == 0.0 {
+        return Some(low);
+    }
+    if y_high == 0.0 {
+        return Some(high);
+    }
+    if y_low.signum() == y_high.signum() {
+        return None;
+    }
+
+    loop {
+        let mid = low + (high - low) / 2.0;
+        if mid == low || mid == high {
+            return Some(mid);
+        }
+        let y_mid = f(mid);
+        if y_mid == 0.0 {
+            return Some(mid);
+        }
+        if y_mid.signum() == y_low.signum() {
+            low = mid;
+            y_low = y_mid;
+        } else {
+            high = mid;
+        }
+    }
+}
+
+fn scan_roots<F>(f: F) -> Vec<f64>
+where
+    F: Fn(f64) -> f64,
+{
+    let mut roots = Vec::new();
+    let mut previous: Option<(f64, f64)> = None;
+    for step in 0..=ROOT_SCAN_STEPS {
+        let growth = ROOT_SCAN_RANGE * (2.0 * step as f64 / ROOT_SCAN_STEPS as f64 - 1.0);
+        let rate = growth.exp_m1();
+        let y = f(rate);
+        if !y.is_finite() {
+            previous = None;
+            continue;
+        }
+
+        if y == 0.0 {
+            roots.push(rate);
+        } else if let Some((previous_rate, previous_y)) = previous {
+            if previous_y != 0.0 && previous_y.signum() != y.signum() {
+                if let Some(root) = bisection(&f, previous_rate, rate) {
+                    roots.push(root);
+                }
+            }
+        }
+        previous = Some((rate, y));
+    }
+    roots
+}
+
+fn nearest_root(roots: Vec<f64>, guess: f64) -> Result<f64, XlsxError> {
+    roots
+        .into_iter()
+        .min_by(|a, b| (a - guess).abs().total_cmp(&(b - guess).abs()))
+        .ok_or(XlsxError::Num)
+}
+
+fn discounted_sum(values: &[f64], rate: f64) -> f64 {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| value / (1.0 + rate).powf(i as f64))
+        .sum()
+}
+
+pub fn irr(values: &[f64], guess: f64) -> Result<f64, XlsxError> {
+    let has_positive = values.iter().any(|&value| value > 0.0);
+    let has_negative = values.iter().any(|&value| value < 0.0);
+    if !has_positive || !has_negative {
+        return Err(XlsxError::Num);
+    }
+
+    let npv_with_derivative = |rate: f64| {
+        values
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(y, dy), (i, value)| {
+                let i = i as f64;
+                (
+                    y + value / (1.0 + rate).powf(i),
+                    dy - i * value / (1.0 + rate).powf(i + 1.0),
+                )
+            })
+    };
+    match newton(
+        npv_with_derivative,
+        guess,
+        IRR_MAX_ITERATIONS,
+        IRR_TOLERANCE,
+    ) {
+        Some(rate) if rate > -1.0 => Ok(rate),
+        _ => nearest_root(irr_roots(values), guess),
+    }
+}
+
+pub fn irr_roots(values: &[f64]) -> Vec<f64> {
+    scan_roots(|rate| discounted_sum(values, rate))
+}
+
+#[cfg(test)]
+mod tests_irr {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        values: &'static [f64],
+        guess: f64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_no_sign_change() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    values: &[70_000.0, 12_000.0, 15_000.0],
+                    guess: 0.1,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[-70_000.0, -12_000.0, 0.0],
+                    guess: 0.1,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[],
+                    guess: 0.1,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = irr(t.args.values, t.args.guess);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 5] = [
+            TestData {
+                args: TestArgs {
+                    values: &[-70_000.0, 12_000.0, 15_000.0, 18_000.0, 21_000.0],
+                    guess: 0.1,
+                },
+                expected: Ok(-0.02124484827341096),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[-70_000.0, 12_000.0, 15_000.0, 18_000.0, 21_000.0, 26_000.0],
+                    guess: 0.1,
+                },
+                expected: Ok(0.08663094803653162),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[-70_000.0, 12_000.0, 15_000.0],
+                    guess: -0.1,
+                },
+                expected: Ok(-0.44350694133474067),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[-100.0, 230.0, -132.0],
+                    guess: 0.1,
+                },
+                expected: Ok(0.10000000000000173),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[-100.0, 230.0, -132.0],
+                    guess: 0.3,
+                },
+                expected: Ok(0.20000000000005846),
+            },
+        ];
+        for t in &test_cases {
+            let actual = irr(t.args.values, t.args.guess);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_newton_diverges() {
+        let actual = irr(
+            &[-70_000.0, 12_000.0, 15_000.0, 18_000.0, 21_000.0, 26_000.0],
+            10.0,
+        );
+        assert_eq!(actual, Ok(0.0866309480365316));
+    }
+}
+
+#[cfg(test)]
+mod tests_irr_roots {
+    use super::*;
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [(&[f64], Vec<f64>); 4] = [
+            (
+                &[-70_000.0, 12_000.0, 15_000.0, 18_000.0, 21_000.0, 26_000.0],
+                vec![0.0866309480365316],
+            ),
+            (
+                &[-100.0, 230.0, -132.0],
+                vec![0.09999999999999859, 0.19999999999999946],
+            ),
+            (&[-100.0, 0.0, 100.0], vec![0.0]),
+            (&[100.0, 230.0], vec![]),
+        ];
+        for (values, expected) in &test_cases {
+            assert_eq!(irr_roots(values), *expected, "values: {:?}", values);
+        }
+    }
+}
+
+const MAX_DATE_SERIAL: i64 = 2_958_465;
+const XIRR_MAX_ITERATIONS: usize = 100;
+const XIRR_TOLERANCE: f64 = 1e-8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CashFlowError {
+    /// There are no cash flows.
+    Empty,
+    /// `values` and `dates` have different lengths.
+    LengthMismatch,
+    /// A date is earlier than the first date.
+    DateBeforeFirst,
+    /// Any other failure Excel reports as `#NUM!`.
+    Num,
+}
+
+impl fmt::Display for CashFlowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CashFlowError::Empty => write!(f, "no cash flows"),
+            CashFlowError::LengthMismatch => write!(f, "values and dates differ in length"),
+            CashFlowError::DateBeforeFirst => write!(f, "a date is earlier than the first date"),
+            CashFlowError::Num => write!(f, "#NUM!"),
+        }
+    }
+}
+
+impl std::error::Error for CashFlowError {}
+
+impl From<CashFlowError> for XlsxError {
+    fn from(_: CashFlowError) -> Self {
+        XlsxError::Num
+    }
+}
+
+fn year_fractions(values: &[f64], dates: &[ExcelDate]) -> Result<Vec<f64>, CashFlowError> {
+    if values.len() != dates.len() {
+        return Err(CashFlowError::LengthMismatch);
+    }
+    let first = match dates.first() {
+        Some(&first) => first,
+        None => return Err(CashFlowError::Empty),
+    };
+    if dates.iter().any(|&date| date < first) {
+        return Err(CashFlowError::DateBeforeFirst);
+    }
+    Ok(dates
+        .iter()
+        .map(|date| (date.serial - first.serial) as f64 / 365.0)
+        .collect())
+}
+
+pub fn xnpv(rate: f64, values: &[f64], dates: &[ExcelDate]) -> Result<f64, CashFlowError> {
+    let years = year_fractions(values, dates)?;
+    if rate <= -1.0 {
+        return Err(CashFlowError::Num);
+    }
+
+    Ok(values
+        .iter()
+        .zip(&years)
+        .map(|(value, year)| value / (1.0 + rate).powf(*year))
+        .sum())
+}
+
+pub fn xirr(values: &[f64], dates: &[ExcelDate], guess: f64) -> Result<f64, CashFlowError> {
+    let years = year_fractions(values, dates)?;
+    let has_positive = values.iter().any(|&value| value > 0.0);
+    let has_negative = values.iter().any(|&value| value < 0.0);
+    if !has_positive || !has_negative {
+        return Err(CashFlowError::Num);
+    }
+
+    let xnpv_at = |rate: f64| {
+        values
+            .iter()
+            .zip(&years)
+            .map(|(value, year)| value / (1.0 + rate).powf(*year))
+            .sum::<f64>()
+    };
+    let xnpv_with_derivative = |rate: f64| {
+        values
+            .iter()
+            .zip(&years)
+            .fold((0.0, 0.0), |(y, dy), (value, year)| {
+                (
+                    y + value / (1.0 + rate).powf(*year),
+                    dy - year * value / (1.0 + rate).powf(year + 1.0),
+                )
+            })
+    };
+    match newton(
+        xnpv_with_derivative,
+        guess,
+        XIRR_MAX_ITERATIONS,
+        XIRR_TOLERANCE,
+    ) {
+        Some(rate) if rate > -1.0 => Ok(rate),
+        _ => nearest_root(scan_roots(xnpv_at), guess).map_err(|_| CashFlowError::Num),
+    }
+}
+
+#[cfg(test)]
+mod fixtures_cash_flows {
+    use super::*;
+
+    pub fn dates(ymds: &[(i64, i64, i64)]) -> Vec<ExcelDate> {
+        ymds.iter()
+            .map(|&(year, month, day)| ExcelDate::from_ymd(year, month, day).unwrap())
+            .collect()
+    }
+
+    pub struct ExcelFixture {
+        pub values: &'static [f64],
+        pub dates: &'static [(i64, i64, i64)],
+        pub xirr: f64,
+    }
+
+    pub const EXCEL_FIXTURES: [ExcelFixture; 6] = [
+        ExcelFixture {
+            values: &[-10_000.0, 2_750.0, 4_250.0, 3_250.0, 2_750.0],
+            dates: &[
+                (2008, 1, 1),
+                (2008, 3, 1),
+                (2008, 10, 30),
+                (2009, 2, 15),
+                (2009, 4, 1),
+            ],
+            xirr: 0.373362535,
+        },
+        ExcelFixture {
+            values: &[-1_000.0, 1_100.0],
+            dates: &[(2019, 1, 1), (2020, 1, 1)],
+            xirr: 0.1,
+        },
+        ExcelFixture {
+            values: &[-1_000.0, 1_210.0],
+            dates: &[(2019, 1, 1), (2020, 12, 31)],
+            xirr: 0.1,
+        },
+        ExcelFixture {
+            values: &[-1_000.0, 550.0, 605.0],
+            dates: &[(2017, 1, 1), (2018, 1, 1), (2019, 1, 1)],
+            xirr: 0.1,
+        },
+        ExcelFixture {
+            values: &[-1_000.0, 605.0, 550.0],
+            dates: &[(2017, 1, 1), (2019, 1, 1), (2018, 1, 1)],
+            xirr: 0.1,
+        },
+        ExcelFixture {
+            values: &[-1_000.0, 900.0],
+            dates: &[(2019, 1, 1), (2020, 1, 1)],
+            xirr: -0.1,
+        },
+    ];
+}
+
+#[cfg(test)]
+mod tests_xnpv {
+    use super::fixtures_cash_flows::{dates, EXCEL_FIXTURES};
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        rate: f64,
+        values: &'static [f64],
+        dates: Vec<ExcelDate>,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, CashFlowError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 4] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.09,
+                    values: &[-10_000.0, 2_750.0, 4_250.0],
+                    dates: dates(&[(2008, 1, 1), (2008, 3, 1)]),
+                },
+                expected: Err(CashFlowError::LengthMismatch),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.09,
+                    values: &[-10_000.0, 2_750.0, 4_250.0],
+                    dates: dates(&[(2008, 3, 1), (2008, 1, 1), (2008, 10, 30)]),
+                },
+                expected: Err(CashFlowError::DateBeforeFirst),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.09,
+                    values: &[],
+                    dates: dates(&[]),
+                },
+                expected: Err(CashFlowError::Empty),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -1.0,
+                    values: &[-10_000.0, 2_750.0, 4_250.0],
+                    dates: dates(&[(2008, 1, 1), (2008, 3, 1), (2008, 10, 30)]),
+                },
+                expected: Err(CashFlowError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = xnpv(t.args.rate, t.args.values, &t.args.dates);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_into_xlsx_error() {
+        let actual = xnpv(0.09, &[-10_000.0], &[]).map_err(XlsxError::from);
+        assert_eq!(actual, Err(XlsxError::Num));
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.09,
+                    values: &[-10_000.0, 2_750.0, 4_250.0, 3_250.0, 2_750.0],
+                    dates: dates(&[
+                        (2008, 1, 1),
+                        (2008, 3, 1),
+                        (2008, 10, 30),
+                        (2009, 2, 15),
+                        (2009, 4, 1),
+                    ]),
+                },
+                expected: Ok(2_086.647602031535),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.0,
+                    values: &[-10_000.0, 2_750.0, 4_250.0, 3_250.0, 2_750.0],
+                    dates: dates(&[
+                        (2008, 1, 1),
+                        (2008, 3, 1),
+                        (2008, 10, 30),
+                        (2009, 2, 15),
+                        (2009, 4, 1),
+                    ]),
+                },
+                expected: Ok(3_000.0),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    values: &[-100.0, 50.0, 60.0],
+                    dates: dates(&[(2008, 1, 1), (2008, 12, 31), (2009, 12, 31)]),
+                },
+                expected: Ok(-4.95867768595042),
+            },
+        ];
+        for t in &test_cases {
+            let actual = xnpv(t.args.rate, t.args.values, &t.args.dates);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_excel_reference() {
+        let actual = xnpv(
+            0.09,
+            &[-10_000.0, 2_750.0, 4_250.0, 3_250.0, 2_750.0],
+            &dates(&[
+                (2008, 1, 1),
+                (2008, 3, 1),
+                (2008, 10, 30),
+                (2009, 2, 15),
+                (2009, 4, 1),
+            ]),
+        )
+        .unwrap();
+        assert!((actual - 2_086.647602).abs() < 1e-6, "actual: {}", actual);
+    }
+
+    #[test]
+    fn test_excel_fixtures() {
+        for fixture in &EXCEL_FIXTURES {
+            let dates = dates(fixture.dates);
+            let actual = xnpv(fixture.xirr, fixture.values, &dates).unwrap();
+            let scale: f64 = fixture.values.iter().map(|value| value.abs()).sum();
+            assert!(
+                (actual / scale).abs() < 1e-8,
+                "dates: {:?}, actual: {}",
+                fixture.dates,
+                actual
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_xirr {
+    use super::fixtures_cash_flows::{dates, EXCEL_FIXTURES};
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        values: &'static [f64],
+        dates: Vec<ExcelDate>,
+        guess: f64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, CashFlowError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    values: &[-10_000.0, 2_750.0, 4_250.0],
+                    dates: dates(&[(2008, 1, 1), (2008, 3, 1)]),
+                    guess: 0.1,
+                },
+                expected: Err(CashFlowError::LengthMismatch),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[-10_000.0, 2_750.0, 4_250.0],
+                    dates: dates(&[(2008, 3, 1), (2008, 1, 1), (2008, 10, 30)]),
+                    guess: 0.1,
+                },
+                expected: Err(CashFlowError::DateBeforeFirst),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[10_000.0, 2_750.0, 4_250.0],
+                    dates: dates(&[(2008, 1, 1), (2008, 3, 1), (2008, 10, 30)]),
+                    guess: 0.1,
+                },
+                expected: Err(CashFlowError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = xirr(t.args.values, &t.args.dates, t.args.guess);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    values: &[-10_000.0, 2_750.0, 4_250.0, 3_250.0, 2_750.0],
+                    dates: dates(&[
+                        (2008, 1, 1),
+                        (2008, 3, 1),
+                        (2008, 10, 30),
+                        (2009, 2, 15),
+                        (2009, 4, 1),
+                    ]),
+                    guess: 0.1,
+                },
+                expected: Ok(0.3733625335188315),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[-10_000.0, 2_750.0, 4_250.0, 3_250.0, 2_750.0],
+                    dates: dates(&[
+                        (2008, 1, 1),
+                        (2008, 3, 1),
+                        (2008, 10, 30),
+                        (2009, 2, 15),
+                        (2009, 4, 1),
+                    ]),
+                    guess: 10.0,
+                },
+                expected: Ok(0.3733625335188314),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[-100.0, 50.0, 60.0],
+                    dates: dates(&[(2008, 1, 1), (2008, 12, 31), (2009, 12, 31)]),
+                    guess: 0.1,
+                },
+                expected: Ok(0.06394102980498532),
+            },
+        ];
+        for t in &test_cases {
+            let actual = xirr(t.args.values, &t.args.dates, t.args.guess);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_excel_fixtures() {
+        for fixture in &EXCEL_FIXTURES {
+            let actual = xirr(fixture.values, &dates(fixture.dates), 0.1).unwrap();
+            assert!(
+                (actual - fixture.xirr).abs() < 1e-8,
+                "dates: {:?}, actual: {}",
+                fixture.dates,
+                actual
+            );
+        }
+    }
+}
+
+pub fn mirr(values: &[f64], finance_rate: f64, reinvest_rate: f64) -> Result<f64, XlsxError> {
+    let positives: Vec<f64> = values.iter().map(|&value| value.max(0.0)).collect();
+    let negatives: Vec<f64> = values.iter().map(|&value| value.min(0.0)).collect();
+    if positives.iter().all(|&value| value == 0.0) || negatives.iter().all(|&value| value == 0.0) {
+        return Err(XlsxError::Div0);
+    }
+
+    let nper_f64 = values.len() as f64;
+    let fv_positives = -npv(reinvest_rate, &positives)? * (1.0 + reinvest_rate).powf(nper_f64);
+    let pv_negatives = npv(finance_rate, &negatives)? * (1.0 + finance_rate);
+    Ok((fv_positives / pv_negatives).powf(1.0 / (nper_f64 - 1.0)) - 1.0)
+}
+
+#[cfg(test)]
+mod tests_mirr {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        values: &'static [f64],
+        finance_rate: f64,
+        reinvest_rate: f64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_no_positive_or_no_negative() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    values: &[120_000.0, 39_000.0, 30_000.0],
+                    finance_rate: 0.1,
+                    reinvest_rate: 0.12,
+                },
+                expected: Err(XlsxError::Div0),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[-120_000.0, -39_000.0, 0.0],
+                    finance_rate: 0.1,
+                    reinvest_rate: 0.12,
+                },
+                expected: Err(XlsxError::Div0),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[],
+                    finance_rate: 0.1,
+                    reinvest_rate: 0.12,
+                },
+                expected: Err(XlsxError::Div0),
+            },
+        ];
+        for t in &test_cases {
+            let actual = mirr(t.args.values, t.args.finance_rate, t.args.reinvest_rate);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 4] = [
+            TestData {
+                args: TestArgs {
+                    values: &[-120_000.0, 39_000.0, 30_000.0, 21_000.0, 37_000.0, 46_000.0],
+                    finance_rate: 0.1,
+                    reinvest_rate: 0.12,
+                },
+                expected: Ok(0.1260941303659051),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[-120_000.0, 39_000.0, 30_000.0, 21_000.0],
+                    finance_rate: 0.1,
+                    reinvest_rate: 0.12,
+                },
+                expected: Ok(-0.048044655249980806),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[-120_000.0, 39_000.0, 30_000.0, 21_000.0, 37_000.0, 46_000.0],
+                    finance_rate: 0.1,
+                    reinvest_rate: 0.14,
+                },
+                expected: Ok(0.13475911082831482),
+            },
+            TestData {
+                args: TestArgs {
+                    values: &[-100.0, 0.0, 121.0],
+                    finance_rate: 0.1,
+                    reinvest_rate: 0.1,
+                },
+                expected: Ok(0.10000000000000009),
+            },
+        ];
+        for t in &test_cases {
+            let actual = mirr(t.args.values, t.args.finance_rate, t.args.reinvest_rate);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+}
+
+pub fn sln(cost: f64, salvage: f64, life: f64) -> Result<f64, XlsxError> {
+    if life == 0.0 {
+        return Err(XlsxError::Div0);
+    }
+    Ok((cost - salvage) / life)
+}
+
+#[cfg(test)]
+mod tests_sln {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        cost: f64,
+        salvage: f64,
+        life: f64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_life_is_0() {
+        let actual = sln(30_000.0, 7_500.0, 0.0);
+        assert_eq!(actual, Err(XlsxError::Div0));
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    cost: 30_000.0,
+                    salvage: 7_500.0,
+                    life: 10.0,
+                },
+                expected: Ok(2_250.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 30_000.0,
+                    salvage: 0.0,
+                    life: 2.5,
+                },
+                expected: Ok(12_000.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 7_500.0,
+                    salvage: 30_000.0,
+                    life: 10.0,
+                },
+                expected: Ok(-2_250.0),
+            },
+        ];
+        for t in &test_cases {
+            let actual = sln(t.args.cost, t.args.salvage, t.args.life);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+}
+
+pub fn syd(cost: f64, salvage: f64, life: f64, per: f64) -> Result<f64, XlsxError> {
+    if life <= 0.0 || per <= 0.0 || per > life {
+        return Err(XlsxError::Num);
+    }
+    Ok((cost - salvage) * (life - per + 1.0) * 2.0 / (life * (life + 1.0)))
+}
+
+#[cfg(test)]
+mod tests_syd {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        cost: f64,
+        salvage: f64,
+        life: f64,
+        per: f64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_life_or_per() {
+        let test_cases: [TestData; 4] = [
+            TestData {
+                args: TestArgs {
+                    cost: 30_000.0,
+                    salvage: 7_500.0,
+                    life: 0.0,
+                    per: 1.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 30_000.0,
+                    salvage: 7_500.0,
+                    life: 10.0,
+                    per: 0.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 30_000.0,
+                    salvage: 7_500.0,
+                    life: 10.0,
+                    per: -1.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 30_000.0,
+                    salvage: 7_500.0,
+                    life: 10.0,
+                    per: 11.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = syd(t.args.cost, t.args.salvage, t.args.life, t.args.per);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    cost: 30_000.0,
+                    salvage: 7_500.0,
+                    life: 10.0,
+                    per: 1.0,
+                },
+                expected: Ok(4_090.909090909091),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 30_000.0,
+                    salvage: 7_500.0,
+                    life: 10.0,
+                    per: 10.0,
+                },
+                expected: Ok(409.09090909090907),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 30_000.0,
+                    salvage: 7_500.0,
+                    life: 10.0,
+                    per: 2.5,
+                },
+                expected: Ok(3_477.2727272727275),
+            },
+        ];
+        for t in &test_cases {
+            let actual = syd(t.args.cost, t.args.salvage, t.args.life, t.args.per);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+}
+
+pub fn db(cost: f64, salvage: f64, life: f64, period: f64, month: i64) -> Result<f64, XlsxError> {
+    if cost < 0.0
+        || salvage < 0.0
+        || life <= 0.0
+        || period <= 0.0
+        || period > life + 1.0
+        || !(1..=12).contains(&month)
+    {
+        return Err(XlsxError::Num);
+    }
+    if cost == 0.0 {
+        return Ok(0.0);
+    }
+
+    let rate = ((1.0 - (salvage / cost).powf(1.0 / life)) * 1_000.0).round() / 1_000.0;
+    let first = cost * rate * month as f64 / 12.0;
+    if period.floor() == 1.0 {
+        return Ok(first);
+    }
+
+    let mut accumulated = first;
+    let mut depreciation = 0.0;
+    for _ in 2..=(life.min(period).floor() as i64) {
+        depreciation = (cost - accumulated) * rate;
+        accumulated += depreciation;
+    }
+    if period > life {
+        depreciation = (cost - accumulated) * rate * (12 - month) as f64 / 12.0;
+    }
+    Ok(depreciation)
+}
+
+#[cfg(test)]
+mod tests_db {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        cost: f64,
+        salvage: f64,
+        life: f64,
+        period: f64,
+        month: i64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 7] = [
+            TestData {
+                args: TestArgs {
+                    cost: -1_000_000.0,
+                    salvage: 100_000.0,
+                    life: 6.0,
+                    period: 1.0,
+                    month: 7,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 1_000_000.0,
+                    salvage: -100_000.0,
+                    life: 6.0,
+                    period: 1.0,
+                    month: 7,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 1_000_000.0,
+                    salvage: 100_000.0,
+                    life: 0.0,
+                    period: 1.0,
+                    month: 7,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 1_000_000.0,
+                    salvage: 100_000.0,
+                    life: 6.0,
+                    period: 0.0,
+                    month: 7,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 1_000_000.0,
+                    salvage: 100_000.0,
+                    life: 6.0,
+                    period: 8.0,
+                    month: 7,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 1_000_000.0,
+                    salvage: 100_000.0,
+                    life: 6.0,
+                    period: 1.0,
+                    month: 0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 1_000_000.0,
+                    salvage: 100_000.0,
+                    life: 6.0,
+                    period: 1.0,
+                    month: 13,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = db(
+                t.args.cost,
+                t.args.salvage,
+                t.args.life,
+                t.args.period,
+                t.args.month,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 9] = [
+            TestData {
+                args: TestArgs {
+                    cost: 1_000_000.0,
+                    salvage: 100_000.0,
+                    life: 6.0,
+                    period: 1.0,
+                    month: 7,
+                },
+                expected: Ok(186_083.33333333334),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 1_000_000.0,
+                    salvage: 100_000.0,
+                    life: 6.0,
+                    period: 2.0,
+                    month: 7,
+                },
+                expected: Ok(259_639.41666666666),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 1_000_000.0,
+                    salvage: 100_000.0,
+                    life: 6.0,
+                    period: 3.0,
+                    month: 7,
+                },
+                expected: Ok(176_814.44275000002),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 1_000_000.0,
+                    salvage: 100_000.0,
+                    life: 6.0,
+                    period: 4.0,
+                    month: 7,
+                },
+                expected: Ok(120_410.63551274998),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 1_000_000.0,
+                    salvage: 100_000.0,
+                    life: 6.0,
+                    period: 5.0,
+                    month: 7,
+                },
+                expected: Ok(81_999.64278418274),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 1_000_000.0,
+                    salvage: 100_000.0,
+                    life: 6.0,
+                    period: 6.0,
+                    month: 7,
+                },
+                expected: Ok(55_841.75673602846),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 1_000_000.0,
+                    salvage: 100_000.0,
+                    life: 6.0,
+                    period: 7.0,
+                    month: 7,
+                },
+                expected: Ok(15_845.098473848071),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 1_000_000.0,
+                    salvage: 100_000.0,
+                    life: 6.0,
+                    period: 1.0,
+                    month: 12,
+                },
+                expected: Ok(319_000.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 0.0,
+                    salvage: 0.0,
+                    life: 6.0,
+                    period: 1.0,
+                    month: 12,
+                },
+                expected: Ok(0.0),
+            },
+        ];
+        for t in &test_cases {
+            let actual = db(
+                t.args.cost,
+                t.args.salvage,
+                t.args.life,
+                t.args.period,
+                t.args.month,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+}
+
+pub fn ddb(cost: f64, salvage: f64, life: f64, period: f64, factor: f64) -> Result<f64, XlsxError> {
+    if cost < 0.0
+        || salvage < 0.0
+        || salvage > cost
+        || life <= 0.0
+        || period <= 0.0
+        || period > life
+        || factor <= 0.0
+    {
+        return Err(XlsxError::Num);
+    }
+    Ok(declining_balance(cost, salvage, life, period, factor))
+}
+
+fn declining_balance(cost: f64, salvage: f64, life: f64, period: f64, factor: f64) -> f64 {
+    let (rate, opening_value) = if factor / life >= 1.0 {
+        (1.0, if period == 1.0 { cost } else { 0.0 })
+    } else {
+        let rate = factor / life;
+        (rate, cost * (1.0 - rate).powf(period - 1.0))
+    };
+    let closing_value = cost * (1.0 - rate).powf(period);
+
+    let depreciation = if closing_value < salvage {
+        opening_value - salvage
+    } else {
+        opening_value - closing_value
+    };
+    depreciation.max(0.0)
+}
+
+#[cfg(test)]
+mod tests_ddb {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        cost: f64,
+        salvage: f64,
+        life: f64,
+        period: f64,
+        factor: f64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 6] = [
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 3_000.0,
+                    life: 10.0,
+                    period: 1.0,
+                    factor: 2.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: -300.0,
+                    life: 10.0,
+                    period: 1.0,
+                    factor: 2.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 0.0,
+                    period: 1.0,
+                    factor: 2.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    period: 0.0,
+                    factor: 2.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    period: 11.0,
+                    factor: 2.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    period: 1.0,
+                    factor: 0.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = ddb(
+                t.args.cost,
+                t.args.salvage,
+                t.args.life,
+                t.args.period,
+                t.args.factor,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 7] = [
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 3_650.0,
+                    period: 1.0,
+                    factor: 2.0,
+                },
+                expected: Ok(1.3150684931506476),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 120.0,
+                    period: 1.0,
+                    factor: 2.0,
+                },
+                expected: Ok(40.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    period: 1.0,
+                    factor: 2.0,
+                },
+                expected: Ok(480.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    period: 2.0,
+                    factor: 1.5,
+                },
+                expected: Ok(306.0000000000002),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    period: 10.0,
+                    factor: 2.0,
+                },
+                expected: Ok(22.122547200000156),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 1.0,
+                    period: 1.0,
+                    factor: 2.0,
+                },
+                expected: Ok(2_100.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 5.0,
+                    period: 5.0,
+                    factor: 2.0,
+                },
+                expected: Ok(11.039999999999964),
+            },
+        ];
+        for t in &test_cases {
+            let actual = ddb(
+                t.args.cost,
+                t.args.salvage,
+                t.args.life,
+                t.args.period,
+                t.args.factor,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+}
+
+pub fn vdb(
+    cost: f64,
+    salvage: f64,
+    life: f64,
+    start_period: f64,
+    end_period: f64,
+    factor: f64,
+    no_switch: bool,
+) -> Result<f64, XlsxError> {
+    if cost < 0.0
+        || salvage < 0.0
+        || salvage > cost
+        || life <= 0.0
+        || start_period < 0.0
+        || end_period < start_period
+        || end_period > life
+        || factor <= 0.0
+    {
+        return Err(XlsxError::Num);
+    }
+
+    let int_start = start_period.floor();
+    let int_end = end_period.ceil();
+
+    if no_switch {
+        let first = int_start as i64 + 1;
+        let last = int_end as i64;
+        return Ok((first..=last)
+            .map(|i| {
+                let depreciation = declining_balance(cost, salvage, life, i as f64, factor);
+                if i == first {
+                    depreciation * (end_period.min(int_start + 1.0) - start_period)
+                } else if i == last {
+                    depreciation * (end_period + 1.0 - int_end)
+                } else {
+                    depreciation
+                }
+            })
+            .sum());
+    }
+
+    let mut part = 0.0;
+    if start_period != int_start {
+        let value = cost - switching_balance(cost, salvage, life, life, int_start, factor);
+        part += (start_period - int_start)
+            * switching_balance(value, salvage, life, life - int_start, 1.0, factor);
+    }
+    if end_period != int_end {
+        let value = cost - switching_balance(cost, salvage, life, life, int_end - 1.0, factor);
+        part += (int_end - end_period)
+            * switching_balance(value, salvage, life, life - (int_end - 1.0), 1.0, factor);
+    }
+
+    let value = cost - switching_balance(cost, salvage, life, life, int_start, factor);
+    Ok(switching_balance(
+        value,
+        salvage,
+        life,
+        life - int_start,
+        int_end - int_start,
+        factor,
+    ) - part)
+}
+
+fn switching_balance(
+    cost: f64,
+    salvage: f64,
+    life: f64,
+    remaining_life: f64,
+    period: f64,
+    factor: f64,
+) -> f64 {
+    let last = period.ceil() as i64;
+    let mut depreciable = cost - salvage;
+    let mut straight_line = None;
+    let mut total = 0.0;
+    for i in 1..=last {
+        let depreciation = match straight_line {
+            Some(depreciation) => depreciation,
+            None => {
+                let declining = declining_balance(cost, salvage, life, i as f64, factor);
+                let linear = depreciable / (remaining_life - (i - 1) as f64);
+                if linear > declining {
+                    straight_line = Some(linear);
+                    linear
+                } else {
+                    depreciable -= declining;
+                    declining
+                }
+            }
+        };
+        total += if i == last {
+            depreciation * (period + 1.0 - last as f64)
+        } else {
+            depreciation
+        };
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests_vdb {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        cost: f64,
+        salvage: f64,
+        life: f64,
+        start_period: f64,
+        end_period: f64,
+        factor: f64,
+        no_switch: bool,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 5] = [
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 3_000.0,
+                    life: 10.0,
+                    start_period: 0.0,
+                    end_period: 1.0,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: -1.0,
+                    end_period: 1.0,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: 2.0,
+                    end_period: 1.0,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: 0.0,
+                    end_period: 11.0,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: 0.0,
+                    end_period: 1.0,
+                    factor: 0.0,
+                    no_switch: false,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = vdb(
+                t.args.cost,
+                t.args.salvage,
+                t.args.life,
+                t.args.start_period,
+                t.args.end_period,
+                t.args.factor,
+                t.args.no_switch,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 9] = [
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 3_650.0,
+                    start_period: 0.0,
+                    end_period: 1.0,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Ok(1.3150684931506476),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 120.0,
+                    start_period: 0.0,
+                    end_period: 1.0,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Ok(40.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: 0.0,
+                    end_period: 1.0,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Ok(480.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 120.0,
+                    start_period: 6.0,
+                    end_period: 18.0,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Ok(396.3060532647519),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 120.0,
+                    start_period: 6.0,
+                    end_period: 18.0,
+                    factor: 1.5,
+                    no_switch: false,
+                },
+                expected: Ok(311.80893665823305),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: 0.0,
+                    end_period: 0.875,
+                    factor: 1.5,
+                    no_switch: false,
+                },
+                expected: Ok(315.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: 0.0,
+                    end_period: 10.0,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Ok(2_100.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: 0.0,
+                    end_period: 10.0,
+                    factor: 2.0,
+                    no_switch: true,
+                },
+                expected: Ok(2_100.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: 1.5,
+                    end_period: 6.25,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Ok(1_130.3116799999996),
+            },
+        ];
+        for t in &test_cases {
+            let actual = vdb(
+                t.args.cost,
+                t.args.salvage,
+                t.args.life,
+                t.args.start_period,
+                t.args.end_period,
+                t.args.factor,
+                t.args.no_switch,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_no_switch() {
+        let test_cases: [TestData; 14] = [
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: 6.0,
+                    end_period: 10.0,
+                    factor: 2.0,
+                    no_switch: true,
+                },
+                expected: Ok(329.1456000000003),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: 6.0,
+                    end_period: 10.0,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Ok(329.1456000000003),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 0.0,
+                    life: 10.0,
+                    start_period: 6.0,
+                    end_period: 10.0,
+                    factor: 2.0,
+                    no_switch: true,
+                },
+                expected: Ok(371.44756224000014),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 0.0,
+                    life: 10.0,
+                    start_period: 6.0,
+                    end_period: 10.0,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Ok(629.1456000000003),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 0.0,
+                    life: 10.0,
+                    start_period: 0.0,
+                    end_period: 10.0,
+                    factor: 2.0,
+                    no_switch: true,
+                },
+                expected: Ok(2142.3019622399997),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 0.0,
+                    life: 10.0,
+                    start_period: 0.0,
+                    end_period: 10.0,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Ok(2399.9999999999995),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 0.0,
+                    life: 10.0,
+                    start_period: 8.0,
+                    end_period: 10.0,
+                    factor: 1.5,
+                    no_switch: true,
+                },
+                expected: Ok(181.47868967601568),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 0.0,
+                    life: 10.0,
+                    start_period: 8.0,
+                    end_period: 10.0,
+                    factor: 1.5,
+                    no_switch: false,
+                },
+                expected: Ok(417.6049999999998),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: 0.5,
+                    end_period: 2.5,
+                    factor: 2.0,
+                    no_switch: true,
+                },
+                expected: Ok(777.5999999999998),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: 0.5,
+                    end_period: 2.5,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Ok(777.5999999999998),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: 1.5,
+                    end_period: 1.75,
+                    factor: 2.0,
+                    no_switch: true,
+                },
+                expected: Ok(95.99999999999994),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 300.0,
+                    life: 10.0,
+                    start_period: 1.5,
+                    end_period: 1.75,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Ok(96.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 0.0,
+                    life: 10.0,
+                    start_period: 6.5,
+                    end_period: 9.25,
+                    factor: 2.0,
+                    no_switch: true,
+                },
+                expected: Ok(260.2146201600001),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    salvage: 0.0,
+                    life: 10.0,
+                    start_period: 6.5,
+                    end_period: 9.25,
+                    factor: 2.0,
+                    no_switch: false,
+                },
+                expected: Ok(432.53759999999994),
+            },
+        ];
+        for t in &test_cases {
+            let actual = vdb(
+                t.args.cost,
+                t.args.salvage,
+                t.args.life,
+                t.args.start_period,
+                t.args.end_period,
+                t.args.factor,
+                t.args.no_switch,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepreciationRow {
+    pub period: i64,
+    pub depreciation: f64,
+    pub accumulated_depreciation: f64,
+    pub book_value: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DepreciationMethod {
+    Sln,
+    Syd,
+    Db { month: i64 },
+    Ddb { factor: f64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct DepreciationSchedule {
+    method: DepreciationMethod,
+    cost: f64,
+    salvage: f64,
+    life: i64,
+    periods: i64,
+    period: i64,
+    accumulated_depreciation: f64,
+}
+
+impl DepreciationSchedule {
+    pub fn sln(cost: f64, salvage: f64, life: i64) -> Result<Self, XlsxError> {
+        DepreciationSchedule::new(DepreciationMethod::Sln, cost, salvage, life)
+    }
+
+    pub fn syd(cost: f64, salvage: f64, life: i64) -> Result<Self, XlsxError> {
+        DepreciationSchedule::new(DepreciationMethod::Syd, cost, salvage, life)
+    }
+
+    pub fn db(cost: f64, salvage: f64, life: i64, month: i64) -> Result<Self, XlsxError> {
+        db(cost, salvage, life as f64, 1.0, month)?;
+        DepreciationSchedule::new(DepreciationMethod::Db { month }, cost, salvage, life)
+    }
+
+    pub fn ddb(cost: f64, salvage: f64, life: i64, factor: f64) -> Result<Self, XlsxError> {
+        ddb(cost, salvage, life as f64, 1.0, factor)?;
+        DepreciationSchedule::new(DepreciationMethod::Ddb { factor }, cost, salvage, life)
+    }
+
+    fn new(
+        method: DepreciationMethod,
+        cost: f64,
+        salvage: f64,
+        life: i64,
+    ) -> Result<Self, XlsxError> {
+        if life < 1 {
+            return Err(XlsxError::Num);
+        }
+        let periods = match method {
+            DepreciationMethod::Db { month } if month < 12 => life + 1,
+            _ => life,
+        };
+        Ok(DepreciationSchedule {
+            method,
+            cost,
+            salvage,
+            life,
+            periods,
+            period: 0,
+            accumulated_depreciation: 0.0,
+        })
+    }
+}
+
+impl Iterator for DepreciationSchedule {
+    type Item = DepreciationRow;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.period >= self.periods {
+            return None;
+        }
+        self.period += 1;
+
+        let life = self.life as f64;
+        let period = self.period as f64;
+        let depreciation = match self.method {
+            DepreciationMethod::Sln => sln(self.cost, self.salvage, life),
+            DepreciationMethod::Syd => syd(self.cost, self.salvage, life, period),
+            DepreciationMethod::Db { month } => db(self.cost, self.salvage, life, period, month),
+            DepreciationMethod::Ddb { factor } => {
+                ddb(self.cost, self.salvage, life, period, factor)
+            }
+        }
+        .ok()?;
+        self.accumulated_depreciation += depreciation;
+
+        Some(DepreciationRow {
+            period: self.period,
+            depreciation,
+            accumulated_depreciation: self.accumulated_depreciation,
+            book_value: self.cost - self.accumulated_depreciation,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.periods - self.period) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for DepreciationSchedule {}
+
+#[cfg(test)]
+mod tests_depreciation_schedule {
+    use super::*;
+
+    #[test]
+    fn test_life_lt_1() {
+        let actual = DepreciationSchedule::sln(30_000.0, 7_500.0, 0);
+        assert_eq!(actual.unwrap_err(), XlsxError::Num);
+        let actual = DepreciationSchedule::syd(30_000.0, 7_500.0, 0);
+        assert_eq!(actual.unwrap_err(), XlsxError::Num);
+    }
+
+    #[test]
+    fn test_sln_rows() {
+        let expected: [DepreciationRow; 3] = [
+            DepreciationRow {
+                period: 1,
+                depreciation: 7_500.0,
+                accumulated_depreciation: 7_500.0,
+                book_value: 22_500.0,
+            },
+            DepreciationRow {
+                period: 2,
+                depreciation: 7_500.0,
+                accumulated_depreciation: 15_000.0,
+                book_value: 15_000.0,
+            },
+            DepreciationRow {
+                period: 3,
+                depreciation: 7_500.0,
+                accumulated_depreciation: 22_500.0,
+                book_value: 7_500.0,
+            },
+        ];
+        let actual: Vec<DepreciationRow> = DepreciationSchedule::sln(30_000.0, 7_500.0, 3)
+            .unwrap()
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_syd_rows() {
+        let expected: [DepreciationRow; 3] = [
+            DepreciationRow {
+                period: 1,
+                depreciation: 11_250.0,
+                accumulated_depreciation: 11_250.0,
+                book_value: 18_750.0,
+            },
+            DepreciationRow {
+                period: 2,
+                depreciation: 7_500.0,
+                accumulated_depreciation: 18_750.0,
+                book_value: 11_250.0,
+            },
+            DepreciationRow {
+                period: 3,
+                depreciation: 3_750.0,
+                accumulated_depreciation: 22_500.0,
+                book_value: 7_500.0,
+            },
+        ];
+        let actual: Vec<DepreciationRow> = DepreciationSchedule::syd(30_000.0, 7_500.0, 3)
+            .unwrap()
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_len() {
+        let mut schedule = DepreciationSchedule::syd(30_000.0, 7_500.0, 10).unwrap();
+        assert_eq!(schedule.len(), 10);
+        schedule.next();
+        assert_eq!(schedule.len(), 9);
+    }
+
+    #[test]
+    fn test_book_value_reaches_salvage() {
+        for schedule in [
+            DepreciationSchedule::sln(30_000.0, 7_500.0, 10).unwrap(),
+            DepreciationSchedule::syd(30_000.0, 7_500.0, 10).unwrap(),
+        ] {
+            let last = schedule.last().unwrap();
+            assert!(
+                (last.book_value - 7_500.0).abs() < 1e-9,
+                "last: {:#?}",
+                last
+            );
+        }
+    }
+
+    #[test]
+    fn test_db_and_ddb_invalid_args() {
+        let actual = DepreciationSchedule::db(1_000_000.0, 100_000.0, 6, 13);
+        assert_eq!(actual.unwrap_err(), XlsxError::Num);
+        let actual = DepreciationSchedule::ddb(2_400.0, 300.0, 10, 0.0);
+        assert_eq!(actual.unwrap_err(), XlsxError::Num);
+    }
+
+    #[test]
+    fn test_db_partial_first_year_adds_period() {
+        let schedule = DepreciationSchedule::db(1_000_000.0, 100_000.0, 6, 7).unwrap();
+        assert_eq!(schedule.len(), 7);
+        let schedule = DepreciationSchedule::db(1_000_000.0, 100_000.0, 6, 12).unwrap();
+        assert_eq!(schedule.len(), 6);
+    }
+
+    #[test]
+    fn test_agree_with_db_and_ddb() {
+        let schedule = DepreciationSchedule::db(1_000_000.0, 100_000.0, 6, 7).unwrap();
+        for row in schedule {
+            let expected = db(1_000_000.0, 100_000.0, 6.0, row.period as f64, 7).unwrap();
+            assert_eq!(row.depreciation, expected, "row: {:#?}", row);
+        }
+        let schedule = DepreciationSchedule::ddb(2_400.0, 300.0, 10, 1.5).unwrap();
+        for row in schedule {
+            let expected = ddb(2_400.0, 300.0, 10.0, row.period as f64, 1.5).unwrap();
+            assert_eq!(row.depreciation, expected, "row: {:#?}", row);
+        }
+    }
+
+    #[test]
+    fn test_ddb_book_value_reaches_salvage() {
+        let last = DepreciationSchedule::ddb(2_400.0, 300.0, 5, 2.0)
+            .unwrap()
+            .last()
+            .unwrap();
+        assert!((last.book_value - 300.0).abs() < 1e-9, "last: {:#?}", last);
+    }
+}
+
+const UNIX_EPOCH_SERIAL: i64 = 25_569;
+const DATE_1904_OFFSET: i64 = 1_462;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateSystem {
+    /// Serial 1 is 1900-01-01, and serial 60 is the nonexistent 1900-02-29.
+    #[default]
+    Date1900,
+    /// Serial 0 is 1904-01-01.
+    Date1904,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExcelDate {
+    serial: i64,
+}
+
+impl ExcelDate {
+    pub fn from_serial(serial: i64, system: DateSystem) -> Result<Self, XlsxError> {
+        let serial = match system {
+            DateSystem::Date1900 => serial,
+            DateSystem::Date1904 => serial + DATE_1904_OFFSET,
+        };
+        let first_serial = match system {
+            DateSystem::Date1900 => 0,
+            DateSystem::Date1904 => DATE_1904_OFFSET,
+        };
+        if !(first_serial..=MAX_DATE_SERIAL).contains(&serial) {
+            return Err(XlsxError::Value);
+        }
+        Ok(ExcelDate { serial })
+    }
+
+    pub fn from_ymd(year: i64, month: i64, day: i64) -> Result<Self, XlsxError> {
+        if !(1900..=9999).contains(&year)
+            || !(1..=12).contains(&month)
+            || day < 1
+            || day > days_in_month(year, month)
+        {
+            return Err(XlsxError::Num);
+        }
+        if (year, month, day) == (1900, 2, 29) {
+            return Ok(ExcelDate { serial: 60 });
+        }
+
+        let shifted_year = if month <= 2 { year - 1 } else { year };
+        let era = shifted_year.div_euclid(400);
+        let year_of_era = shifted_year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let serial = era * 146_097 + day_of_era - 719_468 + UNIX_EPOCH_SERIAL;
+        Ok(ExcelDate {
+            serial: if serial < 61 { serial - 1 } else { serial },
+        })
+    }
+
+    pub fn serial(self, system: DateSystem) -> i64 {
+        match system {
+            DateSystem::Date1900 => self.serial,
+            DateSystem::Date1904 => self.serial - DATE_1904_OFFSET,
+        }
+    }
+
+    pub fn ymd(self) -> (i64, i64, i64) {
+        match self.serial {
+            0 => return (1900, 1, 0),
+            60 => return (1900, 2, 29),
+            _ => {}
+        }
+
+        let days = if self.serial < 60 {
+            self.serial + 1
+        } else {
+            self.serial
+        } - UNIX_EPOCH_SERIAL
+            + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        (year, month, day)
+    }
+
+    pub fn add_months(self, months: i64) -> Result<Self, XlsxError> {
+        let (year, month, day) = self.ymd();
+        let (year, month) = shift_months(year, month, months)?;
+        ExcelDate::from_ymd(year, month, day.clamp(1, days_in_month(year, month)))
+    }
+
+    pub fn end_of_month(self, months: i64) -> Result<Self, XlsxError> {
+        let (year, month, _) = self.ymd();
+        let (year, month) = shift_months(year, month, months)?;
+        ExcelDate::from_ymd(year, month, days_in_month(year, month))
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year == 1900 || is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn shift_months(year: i64, month: i64, months: i64) -> Result<(i64, i64), XlsxError> {
+    let index = year
+        .checked_mul(12)
+        .and_then(|index| index.checked_add(month - 1))
+        .and_then(|index| index.checked_add(months))
+        .ok_or(XlsxError::Num)?;
+    let year = index.div_euclid(12);
+    if !(1900..=9999).contains(&year) {
+        return Err(XlsxError::Num);
+    }
+    Ok((year, index.rem_euclid(12) + 1))
+}
+
+#[cfg(test)]
+mod tests_excel_date {
+    use super::*;
+
+    #[test]
+    fn test_from_serial_out_of_range() {
+        let test_cases: [(i64, DateSystem); 4] = [
+            (-1, DateSystem::Date1900),
+            (2_958_466, DateSystem::Date1900),
+            (-1, DateSystem::Date1904),
+            (2_957_004, DateSystem::Date1904),
+        ];
+        for &(serial, system) in &test_cases {
+            let actual = ExcelDate::from_serial(serial, system);
+            assert_eq!(
+                actual,
+                Err(XlsxError::Value),
+                "serial: {}, system: {:?}",
+                serial,
+                system
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_ymd_invalid() {
+        let test_cases: [(i64, i64, i64); 6] = [
+            (1899, 12, 31),
+            (10_000, 1, 1),
+            (2011, 0, 1),
+            (2011, 13, 1),
+            (2011, 2, 29),
+            (2011, 4, 31),
+        ];
+        for &(year, month, day) in &test_cases {
+            let actual = ExcelDate::from_ymd(year, month, day);
+            assert_eq!(
+                actual,
+                Err(XlsxError::Num),
+                "ymd: {}-{}-{}",
+                year,
+                month,
+                day
+            );
+        }
+    }
+
+    #[test]
+    fn test_date_1900() {
+        let test_cases: [(i64, (i64, i64, i64)); 8] = [
+            (1, (1900, 1, 1)),
+            (59, (1900, 2, 28)),
+            (60, (1900, 2, 29)),
+            (61, (1900, 3, 1)),
+            (1_462, (1904, 1, 1)),
+            (39_813, (2008, 12, 31)),
+            (40_544, (2011, 1, 1)),
+            (2_958_465, (9999, 12, 31)),
+        ];
+        for &(serial, (year, month, day)) in &test_cases {
+            let date = ExcelDate::from_ymd(year, month, day).unwrap();
+            assert_eq!(date.serial(DateSystem::Date1900), serial);
+            let date = ExcelDate::from_serial(serial, DateSystem::Date1900).unwrap();
+            assert_eq!(date.ymd(), (year, month, day));
+        }
+    }
+
+    #[test]
+    fn test_date_1900_serial_0() {
+        let date = ExcelDate::from_serial(0, DateSystem::Date1900).unwrap();
+        assert_eq!(date.ymd(), (1900, 1, 0));
+    }
+
+    #[test]
+    fn test_date_1904() {
+        let test_cases: [(i64, (i64, i64, i64)); 3] = [
+            (0, (1904, 1, 1)),
+            (39_082, (2011, 1, 1)),
+            (2_957_003, (9999, 12, 31)),
+        ];
+        for &(serial, (year, month, day)) in &test_cases {
+            let date = ExcelDate::from_ymd(year, month, day).unwrap();
+            assert_eq!(date.serial(DateSystem::Date1904), serial);
+            let date = ExcelDate::from_serial(serial, DateSystem::Date1904).unwrap();
+            assert_eq!(date.ymd(), (year, month, day));
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for serial in 1..=80_000 {
+            let (year, month, day) = ExcelDate::from_serial(serial, DateSystem::Date1900)
+                .unwrap()
+                .ymd();
+            let actual = ExcelDate::from_ymd(year, month, day).unwrap();
+            assert_eq!(actual.serial(DateSystem::Date1900), serial);
+        }
+    }
+
+    #[test]
+    fn test_add_months() {
+        let date = ExcelDate::from_ymd(2011, 1, 31).unwrap();
+        assert_eq!(date.add_months(1).unwrap().ymd(), (2011, 2, 28));
+        assert_eq!(date.add_months(13).unwrap().ymd(), (2012, 2, 29));
+        assert_eq!(date.add_months(-2).unwrap().ymd(), (2010, 11, 30));
+        assert_eq!(date.add_months(-1_334).unwrap_err(), XlsxError::Num);
+        assert_eq!(date.add_months(i64::MAX).unwrap_err(), XlsxError::Num);
+        assert_eq!(date.add_months(i64::MIN).unwrap_err(), XlsxError::Num);
+    }
+
+    #[test]
+    fn test_end_of_month() {
+        let date = ExcelDate::from_ymd(2011, 1, 15).unwrap();
+        assert_eq!(date.end_of_month(0).unwrap().ymd(), (2011, 1, 31));
+        assert_eq!(date.end_of_month(1).unwrap().ymd(), (2011, 2, 28));
+        assert_eq!(date.end_of_month(-11).unwrap().ymd(), (2010, 2, 28));
+        assert_eq!(date.end_of_month(96_000).unwrap_err(), XlsxError::Num);
+        assert_eq!(date.end_of_month(i64::MAX).unwrap_err(), XlsxError::Num);
+        assert_eq!(date.end_of_month(i64::MIN).unwrap_err(), XlsxError::Num);
+    }
+}
+
+fn is_last_day_of_february(year: i64, month: i64, day: i64) -> bool {
+    month == 2 && day == if is_leap_year(year) { 29 } else { 28 }
+}
+
+fn days_between(start: ExcelDate, end: ExcelDate, basis: DayCountBasis) -> i64 {
+    let (y1, m1, mut d1) = start.ymd();
+    let (y2, m2, mut d2) = end.ymd();
+
+    match basis {
+        DayCountBasis::UsNasd30360 => {
+            if d1 == 31 && d2 == 31 {
+                d1 = 30;
+                d2 = 30;
+            } else if d1 == 31 {
+                d1 = 30;
+            } else if d1 == 30 && d2 == 31 {
+                d2 = 30;
+            } else if is_last_day_of_february(y1, m1, d1) {
+                d1 = 30;
+                if is_last_day_of_february(y2, m2, d2) {
+                    d2 = 30;
+                }
+            }
+            (y2 - y1) * 360 + (m2 - m1) * 30 + (d2 - d1)
+        }
+        DayCountBasis::European30360 => {
+            (y2 - y1) * 360 + (m2 - m1) * 30 + (d2.min(30) - d1.min(30))
+        }
+        DayCountBasis::ActualActual | DayCountBasis::Actual360 | DayCountBasis::Actual365 => {
+            end.serial - start.serial
+        }
+    }
+}
+
+pub fn day_count(start_date: ExcelDate, end_date: ExcelDate, basis: DayCountBasis) -> i64 {
+    if start_date <= end_date {
+        days_between(start_date, end_date, basis)
+    } else {
+        -days_between(end_date, start_date, basis)
+    }
+}
+
+#[cfg(test)]
+mod tests_day_count {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        start_date: ExcelDate,
+        end_date: ExcelDate,
+        basis: DayCountBasis,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: i64,
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 7] = [
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2012, 1, 1).unwrap(),
+                    end_date: ExcelDate::from_ymd(2012, 7, 30).unwrap(),
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: 209,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2012, 1, 1).unwrap(),
+                    end_date: ExcelDate::from_ymd(2012, 7, 30).unwrap(),
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: 211,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 2, 28).unwrap(),
+                    end_date: ExcelDate::from_ymd(2011, 3, 31).unwrap(),
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: 31,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 2, 28).unwrap(),
+                    end_date: ExcelDate::from_ymd(2011, 3, 31).unwrap(),
+                    basis: DayCountBasis::European30360,
+                },
+                expected: 32,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 2, 28).unwrap(),
+                    end_date: ExcelDate::from_ymd(2011, 3, 31).unwrap(),
+                    basis: DayCountBasis::Actual365,
+                },
+                expected: 31,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2012, 2, 29).unwrap(),
+                    end_date: ExcelDate::from_ymd(2013, 2, 28).unwrap(),
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: 360,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2012, 7, 30).unwrap(),
+                    end_date: ExcelDate::from_ymd(2012, 1, 1).unwrap(),
+                    basis: DayCountBasis::Actual360,
+                },
+                expected: -211,
+            },
+        ];
+        for t in &test_cases {
+            let actual = day_count(t.args.start_date, t.args.end_date, t.args.basis);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_serials() {
+        let test_cases: [(DateSystem, i64, i64, i64); 4] = [
+            (DateSystem::Date1900, 40_544, 40_908, 360),
+            (DateSystem::Date1900, 43_861, 43_890, 29),
+            (DateSystem::Date1904, 39_082, 39_446, 360),
+            (DateSystem::Date1904, 42_399, 42_428, 29),
+        ];
+        for &(system, start_date, end_date, expected) in &test_cases {
+            let start_date = ExcelDate::from_serial(start_date, system).unwrap();
+            let end_date = ExcelDate::from_serial(end_date, system).unwrap();
+            let actual = day_count(start_date, end_date, DayCountBasis::UsNasd30360);
+            assert_eq!(actual, expected, "system: {:?}", system);
+        }
+    }
+}
+
+pub fn year_frac(start_date: ExcelDate, end_date: ExcelDate, basis: DayCountBasis) -> f64 {
+    let (start, end) = if start_date <= end_date {
+        (start_date, end_date)
+    } else {
+        (end_date, start_date)
+    };
+    let days = days_between(start, end, basis) as f64;
+
+    let year_length = match basis {
+        DayCountBasis::UsNasd30360 | DayCountBasis::Actual360 | DayCountBasis::European30360 => {
+            360.0
+        }
+        DayCountBasis::Actual365 => 365.0,
+        DayCountBasis::ActualActual => {
+            let (y1, m1, d1) = start.ymd();
+            let (y2, m2, d2) = end.ymd();
+            let within_a_year = y1 == y2 || (y2 == y1 + 1 && (m1 > m2 || (m1 == m2 && d1 >= d2)));
+            if within_a_year {
+                let spans_leap_day = (y1..=y2).filter(|&y| is_leap_year(y)).any(|y| {
+                    let leap_day = (y, 2, 29);
+                    (y1, m1, d1) <= leap_day && leap_day <= (y2, m2, d2)
+                });
+                if (y1 == y2 && is_leap_year(y1)) || spans_leap_day {
+                    366.0
+                } else {
+                    365.0
+                }
+            } else {
+                let total: i64 = (y1..=y2)
+                    .map(|y| if is_leap_year(y) { 366 } else { 365 })
+                    .sum();
+                total as f64 / (y2 - y1 + 1) as f64
+            }
+        }
+    };
+    days / year_length
+}
+
+#[cfg(test)]
+mod tests_year_frac {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        start_date: ExcelDate,
+        end_date: ExcelDate,
+        basis: DayCountBasis,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: f64,
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 11] = [
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2012, 1, 1).unwrap(),
+                    end_date: ExcelDate::from_ymd(2012, 7, 30).unwrap(),
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: 0.5805555555555556,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2012, 1, 1).unwrap(),
+                    end_date: ExcelDate::from_ymd(2012, 7, 30).unwrap(),
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: 0.5765027322404371,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2012, 1, 1).unwrap(),
+                    end_date: ExcelDate::from_ymd(2012, 7, 30).unwrap(),
+                    basis: DayCountBasis::Actual360,
+                },
+                expected: 0.5861111111111111,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2012, 1, 1).unwrap(),
+                    end_date: ExcelDate::from_ymd(2012, 7, 30).unwrap(),
+                    basis: DayCountBasis::Actual365,
+                },
+                expected: 0.5780821917808219,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2012, 1, 1).unwrap(),
+                    end_date: ExcelDate::from_ymd(2012, 7, 30).unwrap(),
+                    basis: DayCountBasis::European30360,
+                },
+                expected: 0.5805555555555556,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2012, 7, 30).unwrap(),
+                    end_date: ExcelDate::from_ymd(2012, 1, 1).unwrap(),
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: 0.5765027322404371,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 1).unwrap(),
+                    end_date: ExcelDate::from_ymd(2013, 6, 30).unwrap(),
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: 2.4936131386861313,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 6, 1).unwrap(),
+                    end_date: ExcelDate::from_ymd(2012, 3, 1).unwrap(),
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: 0.7486338797814208,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 1).unwrap(),
+                    end_date: ExcelDate::from_ymd(2012, 1, 1).unwrap(),
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: 1.0,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 2, 28).unwrap(),
+                    end_date: ExcelDate::from_ymd(2011, 3, 31).unwrap(),
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: 0.08611111111111111,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2012, 1, 1).unwrap(),
+                    end_date: ExcelDate::from_ymd(2012, 1, 1).unwrap(),
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: 0.0,
+            },
+        ];
+        for t in &test_cases {
+            let actual = year_frac(t.args.start_date, t.args.end_date, t.args.basis);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_serials() {
+        let test_cases: [(DateSystem, i64, i64, f64); 4] = [
+            (DateSystem::Date1900, 40_544, 40_908, 0.9972602739726028),
+            (DateSystem::Date1900, 43_861, 43_890, 0.07923497267759563),
+            (DateSystem::Date1904, 39_082, 39_446, 0.9972602739726028),
+            (DateSystem::Date1904, 42_399, 42_428, 0.07923497267759563),
+        ];
+        for &(system, start_date, end_date, expected) in &test_cases {
+            let start_date = ExcelDate::from_serial(start_date, system).unwrap();
+            let end_date = ExcelDate::from_serial(end_date, system).unwrap();
+            let actual = year_frac(start_date, end_date, DayCountBasis::ActualActual);
+            assert_eq!(actual, expected, "system: {:?}", system);
+        }
+    }
+}
+
+fn validate_french_depreciation(
+    cost: f64,
+    date_purchased: ExcelDate,
+    first_period: ExcelDate,
+    salvage: f64,
+    period: i64,
+    rate: f64,
+    basis: DayCountBasis,
+) -> Result<(), XlsxError> {
+    if cost < 0.0
+        || salvage < 0.0
+        || salvage > cost
+        || date_purchased > first_period
+        || period < 0
+        || rate <= 0.0
+        || basis == DayCountBasis::Actual360
+    {
+        return Err(XlsxError::Num);
+    }
+    Ok(())
+}
+
+pub fn amordegrc(
+    cost: f64,
+    date_purchased: ExcelDate,
+    first_period: ExcelDate,
+    salvage: f64,
+    period: i64,
+    rate: f64,
+    basis: DayCountBasis,
+) -> Result<f64, XlsxError> {
+    validate_french_depreciation(
+        cost,
+        date_purchased,
+        first_period,
+        salvage,
+        period,
+        rate,
+        basis,
+    )?;
+
+    let life = 1.0 / rate;
+    if life.fract() != 0.0 && (life < 3.0 || (4.0 < life && life < 5.0)) {
+        return Err(XlsxError::Num);
+    }
+    let coefficient = if life < 3.0 {
+        1.0
+    } else if life < 5.0 {
+        1.5
+    } else if life <= 6.0 {
+        2.0
+    } else {
+        2.5
+    };
+    let rate = rate * coefficient;
+
+    let mut depreciation = (year_frac(date_purchased, first_period, basis) * rate * cost).round();
+    let mut book_value = cost - depreciation;
+    let mut remaining = book_value - salvage;
+    for n in 0..period {
+        depreciation = (rate * book_value).round();
+        remaining -= depreciation;
+        if remaining < 0.0 {
+            return Ok(if period - n == 1 {
+                (book_value * 0.5).round()
+            } else {
+                0.0
+            });
+        }
+        book_value -= depreciation;
+    }
+    Ok(depreciation)
+}
+
+#[cfg(test)]
+mod tests_amordegrc {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        cost: f64,
+        date_purchased: ExcelDate,
+        first_period: ExcelDate,
+        salvage: f64,
+        period: i64,
+        rate: f64,
+        basis: DayCountBasis,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 5] = [
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 3_000.0,
+                    period: 1,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: -1,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 0.0,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 0.15,
+                    basis: DayCountBasis::Actual360,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = amordegrc(
+                t.args.cost,
+                t.args.date_purchased,
+                t.args.first_period,
+                t.args.salvage,
+                t.args.period,
+                t.args.rate,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 7] = [
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 0,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(330.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(776.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 2,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(485.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 3,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(303.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 4,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(190.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 5,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(158.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 0.3,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Ok(902.0),
+            },
+        ];
+        for t in &test_cases {
+            let actual = amordegrc(
+                t.args.cost,
+                t.args.date_purchased,
+                t.args.first_period,
+                t.args.salvage,
+                t.args.period,
+                t.args.rate,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_serials() {
+        let test_cases: [(DateSystem, i64, i64); 2] = [
+            (DateSystem::Date1900, 39_679, 39_813),
+            (DateSystem::Date1904, 38_217, 38_351),
+        ];
+        for &(system, date_purchased, first_period) in &test_cases {
+            let date_purchased = ExcelDate::from_serial(date_purchased, system).unwrap();
+            let first_period = ExcelDate::from_serial(first_period, system).unwrap();
+            let actual = amordegrc(
+                2_400.0,
+                date_purchased,
+                first_period,
+                300.0,
+                0,
+                0.15,
+                DayCountBasis::ActualActual,
+            );
+            assert_eq!(actual, Ok(330.0), "system: {:?}", system);
+        }
+    }
+
+    #[test]
+    fn test_life() {
+        let test_cases: [TestData; 7] = [
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 2.0,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 1.0 / 1.5,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 1.0 / 2.5,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 1.0 / 4.5,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 0.5,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(981.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 0.25,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(776.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 1.0 / 3.5,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(867.0),
+            },
+        ];
+        for t in &test_cases {
+            let actual = amordegrc(
+                t.args.cost,
+                t.args.date_purchased,
+                t.args.first_period,
+                t.args.salvage,
+                t.args.period,
+                t.args.rate,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+}
+
+pub fn amorlinc(
+    cost: f64,
+    date_purchased: ExcelDate,
+    first_period: ExcelDate,
+    salvage: f64,
+    period: i64,
+    rate: f64,
+    basis: DayCountBasis,
+) -> Result<f64, XlsxError> {
+    validate_french_depreciation(
+        cost,
+        date_purchased,
+        first_period,
+        salvage,
+        period,
+        rate,
+        basis,
+    )?;
+
+    let full_rate = cost * rate;
+    let first_rate = year_frac(date_purchased, first_period, basis) * rate * cost;
+    let full_periods = ((cost - salvage - first_rate) / full_rate).floor() as i64;
+
+    let depreciation = if period == 0 {
+        first_rate
+    } else if period <= full_periods {
+        full_rate
+    } else if period == full_periods + 1 {
+        cost - salvage - full_rate * full_periods as f64 - first_rate
+    } else {
+        0.0
+    };
+    Ok(depreciation.max(0.0))
+}
+
+#[cfg(test)]
+mod tests_amorlinc {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        cost: f64,
+        date_purchased: ExcelDate,
+        first_period: ExcelDate,
+        salvage: f64,
+        period: i64,
+        rate: f64,
+        basis: DayCountBasis,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: -0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 0.15,
+                    basis: DayCountBasis::Actual360,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = amorlinc(
+                t.args.cost,
+                t.args.date_purchased,
+                t.args.first_period,
+                t.args.salvage,
+                t.args.period,
+                t.args.rate,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 5] = [
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 0,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(131.8032786885246),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 1,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(360.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 5,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(360.0),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 6,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(168.1967213114754),
+            },
+            TestData {
+                args: TestArgs {
+                    cost: 2_400.0,
+                    date_purchased: ExcelDate::from_ymd(2008, 8, 19).unwrap(),
+                    first_period: ExcelDate::from_ymd(2008, 12, 31).unwrap(),
+                    salvage: 300.0,
+                    period: 7,
+                    rate: 0.15,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(0.0),
+            },
+        ];
+        for t in &test_cases {
+            let actual = amorlinc(
+                t.args.cost,
+                t.args.date_purchased,
+                t.args.first_period,
+                t.args.salvage,
+                t.args.period,
+                t.args.rate,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_serials() {
+        let test_cases: [(DateSystem, i64, i64); 2] = [
+            (DateSystem::Date1900, 39_679, 39_813),
+            (DateSystem::Date1904, 38_217, 38_351),
+        ];
+        for &(system, date_purchased, first_period) in &test_cases {
+            let date_purchased = ExcelDate::from_serial(date_purchased, system).unwrap();
+            let first_period = ExcelDate::from_serial(first_period, system).unwrap();
+            let actual = amorlinc(
+                2_400.0,
+                date_purchased,
+                first_period,
+                300.0,
+                0,
+                0.15,
+                DayCountBasis::ActualActual,
+            );
+            assert_eq!(actual, Ok(131.8032786885246), "system: {:?}", system);
+        }
+    }
+}
+
+pub fn effect(nominal_rate: f64, npery: f64) -> Result<f64, XlsxError> {
+    let npery = npery.trunc();
+    if nominal_rate <= 0.0 || npery < 1.0 {
+        return Err(XlsxError::Num);
+    }
+    Ok((1.0 + nominal_rate / npery).powf(npery) - 1.0)
+}
+
+#[cfg(test)]
+mod tests_effect {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        nominal_rate: f64,
+        npery: f64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    nominal_rate: 0.0,
+                    npery: 4.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    nominal_rate: 0.0525,
+                    npery: 0.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    nominal_rate: 0.0525,
+                    npery: 0.9,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = effect(t.args.nominal_rate, t.args.npery);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    nominal_rate: 0.0525,
+                    npery: 4.0,
+                },
+                expected: Ok(0.05354266737075819),
+            },
+            TestData {
+                args: TestArgs {
+                    nominal_rate: 0.0525,
+                    npery: 4.9,
+                },
+                expected: Ok(0.05354266737075819),
+            },
+            TestData {
+                args: TestArgs {
+                    nominal_rate: 0.12,
+                    npery: 12.0,
+                },
+                expected: Ok(0.12682503013196977),
+            },
+        ];
+        for t in &test_cases {
+            let actual = effect(t.args.nominal_rate, t.args.npery);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+}
+
+pub fn nominal(effect_rate: f64, npery: f64) -> Result<f64, XlsxError> {
+    let npery = npery.trunc();
+    if effect_rate <= 0.0 || npery < 1.0 {
+        return Err(XlsxError::Num);
+    }
+    Ok(npery * ((1.0 + effect_rate).powf(1.0 / npery) - 1.0))
+}
+
+#[cfg(test)]
+mod tests_nominal {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        effect_rate: f64,
+        npery: f64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    effect_rate: -0.053543,
+                    npery: 4.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    effect_rate: 0.053543,
+                    npery: 0.5,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = nominal(t.args.effect_rate, t.args.npery);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    effect_rate: 0.053543,
+                    npery: 4.0,
+                },
+                expected: Ok(0.052500319868356016),
+            },
+            TestData {
+                args: TestArgs {
+                    effect_rate: 0.053543,
+                    npery: 4.5,
+                },
+                expected: Ok(0.052500319868356016),
+            },
+        ];
+        for t in &test_cases {
+            let actual = nominal(t.args.effect_rate, t.args.npery);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_with_effect() {
+        for &npery in &[1.0, 2.0, 4.0, 12.0, 365.0] {
+            let rate = effect(nominal(0.08, npery).unwrap(), npery).unwrap();
+            assert!((rate - 0.08).abs() < 1e-12, "npery: {}", npery);
+        }
+    }
+}
+
+pub fn convert_rate(rate: f64, from_frequency: i64, to_frequency: i64) -> Result<f64, XlsxError> {
+    if from_frequency < 1 || to_frequency < 1 || rate / from_frequency as f64 <= -1.0 {
+        return Err(XlsxError::Num);
+    }
+    let from_frequency = from_frequency as f64;
+    let to_frequency = to_frequency as f64;
+    Ok(to_frequency * ((from_frequency / to_frequency) * (rate / from_frequency).ln_1p()).exp_m1())
+}
+
+#[cfg(test)]
+mod tests_convert_rate {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        rate: f64,
+        from_frequency: i64,
+        to_frequency: i64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.12,
+                    from_frequency: 0,
+                    to_frequency: 12,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.12,
+                    from_frequency: 12,
+                    to_frequency: 0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: -12.0,
+                    from_frequency: 12,
+                    to_frequency: 1,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = convert_rate(t.args.rate, t.args.from_frequency, t.args.to_frequency);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 4] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.12,
+                    from_frequency: 12,
+                    to_frequency: 12,
+                },
+                expected: Ok(0.12),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.12,
+                    from_frequency: 12,
+                    to_frequency: 1,
+                },
+                expected: Ok(0.12682503013196972),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.06,
+                    from_frequency: 1,
+                    to_frequency: 12,
+                },
+                expected: Ok(0.05841060678411644),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.12,
+                    from_frequency: 12,
+                    to_frequency: 4,
+                },
+                expected: Ok(0.12120399999999999),
+            },
+        ];
+        for t in &test_cases {
+            let actual = convert_rate(t.args.rate, t.args.from_frequency, t.args.to_frequency);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_agree_with_effect() {
+        for &npery in &[1, 2, 4, 12, 365] {
+            let annual = convert_rate(0.0525, npery, 1).unwrap();
+            let expected = effect(0.0525, npery as f64).unwrap();
+            assert!((annual - expected).abs() < 1e-12, "npery: {}", npery);
+        }
+    }
+
+    #[test]
+    fn test_agree_with_nominal() {
+        for &npery in &[1, 2, 4, 12, 365] {
+            let actual = convert_rate(0.0525, 1, npery).unwrap();
+            let expected = nominal(0.0525, npery as f64).unwrap();
+            assert!((actual - expected).abs() < 1e-12, "npery: {}", npery);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for &(from_frequency, to_frequency) in &[(12, 1), (1, 12), (4, 365), (2, 2)] {
+            let converted = convert_rate(0.0525, from_frequency, to_frequency).unwrap();
+            let actual = convert_rate(converted, to_frequency, from_frequency).unwrap();
+            assert!(
+                (actual - 0.0525).abs() < 1e-12,
+                "from_frequency: {}, to_frequency: {}",
+                from_frequency,
+                to_frequency
+            );
+        }
+    }
+}
+
+pub fn rri(nper: i64, pv: f64, fv: f64) -> Result<f64, XlsxError> {
+    if nper <= 0 || pv == 0.0 || fv / pv < 0.0 {
+        return Err(XlsxError::Num);
+    }
+    Ok((fv / pv).powf(1.0 / nper as f64) - 1.0)
+}
+
+#[cfg(test)]
+mod tests_rri {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        nper: i64,
+        pv: f64,
+        fv: f64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    nper: 0,
+                    pv: 10_000.0,
+                    fv: 11_000.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    nper: 96,
+                    pv: 0.0,
+                    fv: 11_000.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    nper: 96,
+                    pv: -10_000.0,
+                    fv: 11_000.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = rri(t.args.nper, t.args.pv, t.args.fv);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    nper: 96,
+                    pv: 10_000.0,
+                    fv: 11_000.0,
+                },
+                expected: Ok(0.0009933073762913303),
+            },
+            TestData {
+                args: TestArgs {
+                    nper: 10,
+                    pv: 1_000.0,
+                    fv: 500.0,
+                },
+                expected: Ok(-0.06696700846319259),
+            },
+            TestData {
+                args: TestArgs {
+                    nper: 5,
+                    pv: 1_000.0,
+                    fv: 1_000.0,
+                },
+                expected: Ok(0.0),
+            },
+        ];
+        for t in &test_cases {
+            let actual = rri(t.args.nper, t.args.pv, t.args.fv);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_with_fv() {
+        let test_cases: [(i64, f64, f64); 3] = [
+            (96, 10_000.0, 11_000.0),
+            (10, 1_000.0, 500.0),
+            (360, 250_000.0, 1_000_000.0),
+        ];
+        for &(nper, pv, expected) in &test_cases {
+            let rate = rri(nper, pv, expected).unwrap();
+            let actual = fv(rate, nper, 0.0, -pv, PaymentTiming::End).unwrap();
+            assert!(
+                (actual - expected).abs() < 1e-6,
+                "nper: {}, pv: {}, fv: {}",
+                nper,
+                pv,
+                expected
+            );
+        }
+    }
+}
+
+pub fn pduration(rate: f64, pv: f64, fv: f64) -> Result<f64, XlsxError> {
+    if rate <= 0.0 || pv <= 0.0 || fv <= 0.0 {
+        return Err(XlsxError::Num);
+    }
+    Ok((fv.ln() - pv.ln()) / rate.ln_1p())
+}
+
+#[cfg(test)]
+mod tests_pduration {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        rate: f64,
+        pv: f64,
+        fv: f64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.0,
+                    pv: 2_000.0,
+                    fv: 2_200.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.025,
+                    pv: 0.0,
+                    fv: 2_200.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.025,
+                    pv: 2_000.0,
+                    fv: -2_200.0,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = pduration(t.args.rate, t.args.pv, t.args.fv);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.025,
+                    pv: 2_000.0,
+                    fv: 2_200.0,
+                },
+                expected: Ok(3.8598661626226414),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.025 / 12.0,
+                    pv: 1_000.0,
+                    fv: 1_200.0,
+                },
+                expected: Ok(87.60547641937576),
+            },
+        ];
+        for t in &test_cases {
+            let actual = pduration(t.args.rate, t.args.pv, t.args.fv);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_agree_with_rri() {
+        let periods = pduration(rri(96, 10_000.0, 11_000.0).unwrap(), 10_000.0, 11_000.0).unwrap();
+        assert!((periods - 96.0).abs() < 1e-9, "periods: {}", periods);
+    }
+}
+
+pub fn ispmt(rate: f64, per: i64, nper: i64, pv: f64) -> Result<f64, XlsxError> {
+    if nper == 0 {
+        return Err(XlsxError::Div0);
+    }
+    Ok(pv * rate * (per as f64 / nper as f64 - 1.0))
+}
+
+#[cfg(test)]
+mod tests_ispmt {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        rate: f64,
+        per: i64,
+        nper: i64,
+        pv: f64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_nper_is_0() {
+        let actual = ispmt(0.1, 1, 0, 8_000_000.0);
+        assert_eq!(actual, Err(XlsxError::Div0));
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 4] = [
+            TestData {
+                args: TestArgs {
+                    rate: 0.1 / 12.0,
+                    per: 1,
+                    nper: 36,
+                    pv: 8_000_000.0,
+                },
+                expected: Ok(-64_814.81481481482),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    per: 1,
+                    nper: 3,
+                    pv: 8_000_000.0,
+                },
+                expected: Ok(-533_333.3333333334),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    per: 0,
+                    nper: 4,
+                    pv: 4_000.0,
+                },
+                expected: Ok(-400.0),
+            },
+            TestData {
+                args: TestArgs {
+                    rate: 0.1,
+                    per: 4,
+                    nper: 4,
+                    pv: 4_000.0,
+                },
+                expected: Ok(0.0),
+            },
+        ];
+        for t in &test_cases {
+            let actual = ispmt(t.args.rate, t.args.per, t.args.nper, t.args.pv);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EqualPrincipalSchedule {
+    rate: f64,
+    nper: i64,
+    pv: f64,
+    period: i64,
+}
+
+impl EqualPrincipalSchedule {
+    pub fn new(rate: f64, nper: i64, pv: f64) -> Result<Self, XlsxError> {
+        if nper < 1 {
+            return Err(XlsxError::Num);
+        }
+
+        Ok(EqualPrincipalSchedule {
+            rate,
+            nper,
+            pv,
+            period: 0,
+        })
+    }
+}
+
+impl Iterator for EqualPrincipalSchedule {
+    type Item = AmortizationRow;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.period >= self.nper {
+            return None;
+        }
+        self.period += 1;
+
+        let nper = self.nper as f64;
+        let opening_balance = self.pv * (1.0 - (self.period - 1) as f64 / nper);
+        let closing_balance = self.pv * (1.0 - self.period as f64 / nper);
+        let interest = ispmt(self.rate, self.period - 1, self.nper, self.pv).ok()?;
+        let principal = -self.pv / nper;
+
+        Some(AmortizationRow {
+            period: self.period,
+            payment: interest + principal,
+            interest,
+            principal,
+            opening_balance,
+            closing_balance,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.nper - self.period) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for EqualPrincipalSchedule {}
+
+#[cfg(test)]
+mod tests_equal_principal_schedule {
+    use super::*;
+
+    #[test]
+    fn test_nper_lt_1() {
+        let actual = EqualPrincipalSchedule::new(0.1, 0, 4_000.0);
+        assert_eq!(actual.unwrap_err(), XlsxError::Num);
+    }
+
+    #[test]
+    fn test_rows() {
+        let expected: [AmortizationRow; 4] = [
+            AmortizationRow {
+                period: 1,
+                payment: -1_400.0,
+                interest: -400.0,
+                principal: -1_000.0,
+                opening_balance: 4_000.0,
+                closing_balance: 3_000.0,
+            },
+            AmortizationRow {
+                period: 2,
+                payment: -1_300.0,
+                interest: -300.0,
+                principal: -1_000.0,
+                opening_balance: 3_000.0,
+                closing_balance: 2_000.0,
+            },
+            AmortizationRow {
+                period: 3,
+                payment: -1_200.0,
+                interest: -200.0,
+                principal: -1_000.0,
+                opening_balance: 2_000.0,
+                closing_balance: 1_000.0,
+            },
+            AmortizationRow {
+                period: 4,
+                payment: -1_100.0,
+                interest: -100.0,
+                principal: -1_000.0,
+                opening_balance: 1_000.0,
+                closing_balance: 0.0,
+            },
+        ];
+        let actual: Vec<AmortizationRow> = EqualPrincipalSchedule::new(0.1, 4, 4_000.0)
+            .unwrap()
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_len() {
+        let mut schedule = EqualPrincipalSchedule::new(0.1 / 12.0, 36, 8_000_000.0).unwrap();
+        assert_eq!(schedule.len(), 36);
+        schedule.next();
+        assert_eq!(schedule.len(), 35);
+    }
+
+    #[test]
+    fn test_agree_with_ispmt() {
+        let schedule = EqualPrincipalSchedule::new(0.1 / 12.0, 36, 8_000_000.0).unwrap();
+        for row in schedule {
+            let interest = ispmt(0.1 / 12.0, row.period - 1, 36, 8_000_000.0).unwrap();
+            assert_eq!(row.interest, interest, "row: {:#?}", row);
+            assert!(
+                (row.opening_balance + row.principal - row.closing_balance).abs() < 1e-6,
+                "row: {:#?}",
+                row
+            );
+        }
+    }
+}
+
+pub fn fvschedule(principal: f64, schedule: &[f64]) -> f64 {
+    schedule
+        .iter()
+        .fold(principal, |value, rate| value * (1.0 + rate))
+}
+
+#[cfg(test)]
+mod tests_fvschedule {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        principal: f64,
+        schedule: &'static [f64],
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: f64,
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 4] = [
+            TestData {
+                args: TestArgs {
+                    principal: 1.0,
+                    schedule: &[0.09, 0.11, 0.1],
+                },
+                expected: 1.3308900000000004,
+            },
+            TestData {
+                args: TestArgs {
+                    principal: 10_000.0,
+                    schedule: &[0.01, -0.005, 0.0, 0.02],
+                },
+                expected: 10_250.49,
+            },
+            TestData {
+                args: TestArgs {
+                    principal: 10_000.0,
+                    schedule: &[],
+                },
+                expected: 10_000.0,
+            },
+            TestData {
+                args: TestArgs {
+                    principal: 10_000.0,
+                    schedule: &[0.05, 0.05, 0.05],
+                },
+                expected: 11_576.25,
+            },
+        ];
+        for t in &test_cases {
+            let actual = fvschedule(t.args.principal, t.args.schedule);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+}
+
+pub fn fv_variable(
+    rates: &[f64],
+    payments: &[f64],
+    pv: f64,
+    timing: PaymentTiming,
+) -> Result<f64, XlsxError> {
+    if rates.len() != payments.len() {
+        return Err(XlsxError::Num);
+    }
+
+    let balance = rates
+        .iter()
+        .zip(payments)
+        .fold(pv, |balance, (rate, payment)| match timing {
+            PaymentTiming::End => balance * (1.0 + rate) + payment,
+            PaymentTiming::Beginning => (balance + payment) * (1.0 + rate),
+        });
+    Ok(-balance)
+}
+
+#[cfg(test)]
+mod tests_fv_variable {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        rates: &'static [f64],
+        payments: &'static [f64],
+        pv: f64,
+        timing: PaymentTiming,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_length_mismatch() {
+        let actual = fv_variable(&[0.01, 0.02], &[-100.0], 0.0, PaymentTiming::End);
+        assert_eq!(actual, Err(XlsxError::Num));
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    rates: &[0.01, 0.02, 0.03],
+                    payments: &[-100.0, -100.0, -100.0],
+                    pv: -1_000.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(1_369.1660000000002),
+            },
+            TestData {
+                args: TestArgs {
+                    rates: &[0.01, 0.02, 0.03],
+                    payments: &[-100.0, -100.0, -100.0],
+                    pv: -1_000.0,
+                    timing: PaymentTiming::Beginning,
+                },
+                expected: Ok(1_375.2766000000001),
+            },
+            TestData {
+                args: TestArgs {
+                    rates: &[],
+                    payments: &[],
+                    pv: -1_000.0,
+                    timing: PaymentTiming::End,
+                },
+                expected: Ok(1_000.0),
+            },
+        ];
+        for t in &test_cases {
+            let actual = fv_variable(t.args.rates, t.args.payments, t.args.pv, t.args.timing);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_agree_with_fv() {
+        let rates = [0.06 / 12.0; 10];
+        let payments = [-200.0; 10];
+        for &timing in &[PaymentTiming::End, PaymentTiming::Beginning] {
+            let actual = fv_variable(&rates, &payments, -500.0, timing).unwrap();
+            let expected = fv(0.06 / 12.0, 10, -200.0, -500.0, timing).unwrap();
+            assert!(
+                (actual - expected).abs() < 1e-9,
+                "timing: {:?}, actual: {}, fv: {}",
+                timing,
+                actual,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_agree_with_fvschedule() {
+        let rates = [0.09, 0.11, 0.1];
+        let actual = fv_variable(&rates, &[0.0; 3], -1.0, PaymentTiming::End).unwrap();
+        assert!((actual - fvschedule(1.0, &rates)).abs() < 1e-12);
+    }
+}
+
+pub fn edate(start_date: ExcelDate, months: i64) -> Result<ExcelDate, XlsxError> {
+    start_date.add_months(months)
+}
+
+#[cfg(test)]
+mod tests_edate {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        start_date: ExcelDate,
+        months: i64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<ExcelDate, XlsxError>,
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(9999, 12, 31).unwrap(),
+                    months: 1,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(1900, 1, 1).unwrap(),
+                    months: -1,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 15).unwrap(),
+                    months: i64::MAX,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = edate(t.args.start_date, t.args.months);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 5] = [
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 15).unwrap(),
+                    months: 1,
+                },
+                expected: ExcelDate::from_ymd(2011, 2, 15),
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 15).unwrap(),
+                    months: -1,
+                },
+                expected: ExcelDate::from_ymd(2010, 12, 15),
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 15).unwrap(),
+                    months: 2,
+                },
+                expected: ExcelDate::from_ymd(2011, 3, 15),
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 31).unwrap(),
+                    months: 1,
+                },
+                expected: ExcelDate::from_ymd(2011, 2, 28),
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2020, 1, 31).unwrap(),
+                    months: 1,
+                },
+                expected: ExcelDate::from_ymd(2020, 2, 29),
+            },
+        ];
+        for t in &test_cases {
+            let actual = edate(t.args.start_date, t.args.months);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_serials() {
+        let test_cases: [(DateSystem, i64, i64); 5] = [
+            (DateSystem::Date1900, 0, 32),
+            (DateSystem::Date1900, 40_558, 40_589),
+            (DateSystem::Date1900, 43_861, 43_890),
+            (DateSystem::Date1904, 39_096, 39_127),
+            (DateSystem::Date1904, 42_399, 42_428),
+        ];
+        for &(system, start_date, expected) in &test_cases {
+            let start_date = ExcelDate::from_serial(start_date, system).unwrap();
+            let actual = edate(start_date, 1).unwrap().serial(system);
+            assert_eq!(actual, expected, "system: {:?}", system);
+        }
+    }
+}
+
+pub fn eomonth(start_date: ExcelDate, months: i64) -> Result<ExcelDate, XlsxError> {
+    start_date.end_of_month(months)
+}
+
+#[cfg(test)]
+mod tests_eomonth {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        start_date: ExcelDate,
+        months: i64,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<ExcelDate, XlsxError>,
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(9999, 12, 1).unwrap(),
+                    months: 1,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 1).unwrap(),
+                    months: i64::MIN,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = eomonth(t.args.start_date, t.args.months);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 4] = [
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 1).unwrap(),
+                    months: 1,
+                },
+                expected: ExcelDate::from_ymd(2011, 2, 28),
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 1).unwrap(),
+                    months: -3,
+                },
+                expected: ExcelDate::from_ymd(2010, 10, 31),
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 1).unwrap(),
+                    months: 0,
+                },
+                expected: ExcelDate::from_ymd(2011, 1, 31),
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2020, 1, 15).unwrap(),
+                    months: 1,
+                },
+                expected: ExcelDate::from_ymd(2020, 2, 29),
+            },
+        ];
+        for t in &test_cases {
+            let actual = eomonth(t.args.start_date, t.args.months);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_serials() {
+        let test_cases: [(DateSystem, i64, i64); 5] = [
+            (DateSystem::Date1900, 0, 60),
+            (DateSystem::Date1900, 40_544, 40_602),
+            (DateSystem::Date1900, 43_861, 43_890),
+            (DateSystem::Date1904, 39_082, 39_140),
+            (DateSystem::Date1904, 42_399, 42_428),
+        ];
+        for &(system, start_date, expected) in &test_cases {
+            let start_date = ExcelDate::from_serial(start_date, system).unwrap();
+            let actual = eomonth(start_date, 1).unwrap().serial(system);
+            assert_eq!(actual, expected, "system: {:?}", system);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Days360Method {
+    /// US (NASD) method (Excel `method` FALSE).
+    #[default]
+    Us,
+    /// European method (Excel `method` TRUE).
+    European,
+}
+
+impl From<bool> for Days360Method {
+    fn from(european: bool) -> Self {
+        if european {
+            Days360Method::European
+        } else {
+            Days360Method::Us
+        }
+    }
+}
+
+pub fn days360(start_date: ExcelDate, end_date: ExcelDate, method: Days360Method) -> i64 {
+    let (y1, m1, mut d1) = start_date.ymd();
+    let (y2, m2, mut d2) = end_date.ymd();
+
+    match method {
+        Days360Method::Us => {
+            if d1 == 31 || (m1 == 2 && d1 == days_in_month(y1, m1)) {
+                d1 = 30;
+            }
+            if d2 == 31 && d1 == 30 {
+                d2 = 30;
+            }
+        }
+        Days360Method::European => {
+            d1 = d1.min(30);
+            d2 = d2.min(30);
+        }
+    }
+    (y2 - y1) * 360 + (m2 - m1) * 30 + (d2 - d1)
+}
+
+#[cfg(test)]
+mod tests_days360 {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        start_date: ExcelDate,
+        end_date: ExcelDate,
+        method: Days360Method,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: i64,
+    }
+
+    #[test]
+    fn test_from_bool() {
+        assert_eq!(Days360Method::from(false), Days360Method::Us);
+        assert_eq!(Days360Method::from(true), Days360Method::European);
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 8] = [
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 1).unwrap(),
+                    end_date: ExcelDate::from_ymd(2011, 12, 31).unwrap(),
+                    method: Days360Method::Us,
+                },
+                expected: 360,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 30).unwrap(),
+                    end_date: ExcelDate::from_ymd(2011, 2, 1).unwrap(),
+                    method: Days360Method::Us,
+                },
+                expected: 1,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 2, 28).unwrap(),
+                    end_date: ExcelDate::from_ymd(2011, 3, 31).unwrap(),
+                    method: Days360Method::Us,
+                },
+                expected: 30,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 2, 28).unwrap(),
+                    end_date: ExcelDate::from_ymd(2011, 3, 31).unwrap(),
+                    method: Days360Method::European,
+                },
+                expected: 32,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 15).unwrap(),
+                    end_date: ExcelDate::from_ymd(2011, 3, 31).unwrap(),
+                    method: Days360Method::Us,
+                },
+                expected: 76,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 15).unwrap(),
+                    end_date: ExcelDate::from_ymd(2011, 3, 31).unwrap(),
+                    method: Days360Method::European,
+                },
+                expected: 75,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 3, 31).unwrap(),
+                    end_date: ExcelDate::from_ymd(2011, 1, 30).unwrap(),
+                    method: Days360Method::Us,
+                },
+                expected: -60,
+            },
+            TestData {
+                args: TestArgs {
+                    start_date: ExcelDate::from_ymd(2011, 1, 31).unwrap(),
+                    end_date: ExcelDate::from_ymd(2011, 3, 31).unwrap(),
+                    method: Days360Method::Us,
+                },
+                expected: 60,
+            },
+        ];
+        for t in &test_cases {
+            let actual = days360(t.args.start_date, t.args.end_date, t.args.method);
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_serials() {
+        let test_cases: [(DateSystem, i64, i64, i64); 4] = [
+            (DateSystem::Date1900, 40_544, 40_908, 360),
+            (DateSystem::Date1900, 43_861, 43_921, 60),
+            (DateSystem::Date1904, 39_082, 39_446, 360),
+            (DateSystem::Date1904, 42_399, 42_459, 60),
+        ];
+        for &(system, start_date, end_date, expected) in &test_cases {
+            let start_date = ExcelDate::from_serial(start_date, system).unwrap();
+            let end_date = ExcelDate::from_serial(end_date, system).unwrap();
+            let actual = days360(start_date, end_date, Days360Method::Us);
+            assert_eq!(actual, expected, "system: {:?}", system);
+        }
+    }
+}
+
+fn coupon_date(
+    maturity: ExcelDate,
+    periods_before: i64,
+    frequency: CouponFrequency,
+) -> Result<ExcelDate, XlsxError> {
+    let (year, month, day) = maturity.ymd();
+    let end_of_month = day == days_in_month(year, month);
+    let (year, month) = shift_months(year, month, -periods_before * 12 / frequency.per_year())?;
+    let last_day = days_in_month(year, month);
+    ExcelDate::from_ymd(
+        year,
+        month,
+        if end_of_month {
+            last_day
+        } else {
+            day.min(last_day)
+        },
+    )
+}
+
+fn coupon_period(
+    settlement: ExcelDate,
+    maturity: ExcelDate,
+    frequency: CouponFrequency,
+) -> Result<(ExcelDate, ExcelDate, i64), XlsxError> {
+    if settlement >= maturity {
+        return Err(XlsxError::Num);
+    }
+
+    let (settlement_year, settlement_month, _) = settlement.ymd();
+    let (maturity_year, maturity_month, _) = maturity.ymd();
+    let months = (maturity_year - settlement_year) * 12 + maturity_month - settlement_month;
+    let mut remaining = (months * frequency.per_year() / 12).max(1);
+    while coupon_date(maturity, remaining, frequency)? > settlement {
+        remaining += 1;
+    }
+    while remaining > 1 && coupon_date(maturity, remaining - 1, frequency)? <= settlement {
+        remaining -= 1;
+    }
+
+    Ok((
+        coupon_date(maturity, remaining, frequency)?,
+        coupon_date(maturity, remaining - 1, frequency)?,
+        remaining,
+    ))
+}
+
+pub fn coupdaybs(
+    settlement: ExcelDate,
+    maturity: ExcelDate,
+    frequency: CouponFrequency,
+    basis: DayCountBasis,
+) -> Result<i64, XlsxError> {
+    let (previous, _, _) = coupon_period(settlement, maturity, frequency)?;
+    Ok(days_between(previous, settlement, basis))
+}
+
+#[cfg(test)]
+mod tests_coupdaybs {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        settlement: ExcelDate,
+        maturity: ExcelDate,
+        frequency: CouponFrequency,
+        basis: DayCountBasis,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<i64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = coupdaybs(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 5] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(71),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Ok(70),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Quarterly,
+                    basis: DayCountBasis::Actual365,
+                },
+                expected: Ok(71),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 3, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 30).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Ok(105),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 3, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 30).unwrap(),
+                    frequency: CouponFrequency::Quarterly,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(15),
+            },
+        ];
+        for t in &test_cases {
+            let actual = coupdaybs(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+}
+
+pub fn coupdays(
+    settlement: ExcelDate,
+    maturity: ExcelDate,
+    frequency: CouponFrequency,
+    basis: DayCountBasis,
+) -> Result<f64, XlsxError> {
+    let (previous, next, _) = coupon_period(settlement, maturity, frequency)?;
+    let per_year = frequency.per_year() as f64;
+    Ok(match basis {
+        DayCountBasis::ActualActual => (next.serial - previous.serial) as f64,
+        DayCountBasis::Actual365 => 365.0 / per_year,
+        DayCountBasis::UsNasd30360 | DayCountBasis::Actual360 | DayCountBasis::European30360 => {
+            360.0 / per_year
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests_coupdays {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        settlement: ExcelDate,
+        maturity: ExcelDate,
+        frequency: CouponFrequency,
+        basis: DayCountBasis,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = coupdays(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 5] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(181.0),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Ok(180.0),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::Actual365,
+                },
+                expected: Ok(182.5),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Quarterly,
+                    basis: DayCountBasis::Actual360,
+                },
+                expected: Ok(90.0),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 3, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 30).unwrap(),
+                    frequency: CouponFrequency::Annual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(365.0),
+            },
+        ];
+        for t in &test_cases {
+            let actual = coupdays(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+}
+
+pub fn coupdaysnc(
+    settlement: ExcelDate,
+    maturity: ExcelDate,
+    frequency: CouponFrequency,
+    basis: DayCountBasis,
+) -> Result<i64, XlsxError> {
+    let (previous, next, _) = coupon_period(settlement, maturity, frequency)?;
+    match basis {
+        DayCountBasis::UsNasd30360 | DayCountBasis::European30360 => {
+            Ok(360 / frequency.per_year() - days_between(previous, settlement, basis))
+        }
+        DayCountBasis::ActualActual | DayCountBasis::Actual360 | DayCountBasis::Actual365 => {
+            Ok(next.serial - settlement.serial)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_coupdaysnc {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        settlement: ExcelDate,
+        maturity: ExcelDate,
+        frequency: CouponFrequency,
+        basis: DayCountBasis,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<i64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = coupdaysnc(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 5] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(110),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Ok(110),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::European30360,
+                },
+                expected: Ok(110),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 3, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 30).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(77),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 3, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 30).unwrap(),
+                    frequency: CouponFrequency::Quarterly,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Ok(75),
+            },
+        ];
+        for t in &test_cases {
+            let actual = coupdaysnc(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_serials() {
+        let test_cases: [(DateSystem, i64, i64, i64); 2] = [
+            (DateSystem::Date1900, 40_568, 40_862, 110),
+            (DateSystem::Date1904, 39_106, 39_400, 110),
+        ];
+        for &(system, settlement, maturity, expected) in &test_cases {
+            let settlement = ExcelDate::from_serial(settlement, system).unwrap();
+            let maturity = ExcelDate::from_serial(maturity, system).unwrap();
+            let actual = coupdaysnc(
+                settlement,
+                maturity,
+                CouponFrequency::Semiannual,
+                DayCountBasis::ActualActual,
+            );
+            assert_eq!(actual, Ok(expected), "system: {:?}", system);
+        }
+    }
+}
+
+pub fn coupncd(
+    settlement: ExcelDate,
+    maturity: ExcelDate,
+    frequency: CouponFrequency,
+    _basis: DayCountBasis,
+) -> Result<ExcelDate, XlsxError> {
+    let (_, next, _) = coupon_period(settlement, maturity, frequency)?;
+    Ok(next)
+}
+
+#[cfg(test)]
+mod tests_coupncd {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        settlement: ExcelDate,
+        maturity: ExcelDate,
+        frequency: CouponFrequency,
+        basis: DayCountBasis,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<ExcelDate, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = coupncd(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 5] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: ExcelDate::from_ymd(2011, 5, 15),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Annual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: ExcelDate::from_ymd(2011, 11, 15),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 3, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 30).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: ExcelDate::from_ymd(2011, 5, 31),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 3, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 30).unwrap(),
+                    frequency: CouponFrequency::Quarterly,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: ExcelDate::from_ymd(2011, 5, 31),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 3, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 29).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: ExcelDate::from_ymd(2011, 5, 29),
+            },
+        ];
+        for t in &test_cases {
+            let actual = coupncd(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_serials() {
+        let test_cases: [(DateSystem, i64, i64, i64); 2] = [
+            (DateSystem::Date1900, 40_568, 40_862, 40_678),
+            (DateSystem::Date1904, 39_106, 39_400, 39_216),
+        ];
+        for &(system, settlement, maturity, expected) in &test_cases {
+            let settlement = ExcelDate::from_serial(settlement, system).unwrap();
+            let maturity = ExcelDate::from_serial(maturity, system).unwrap();
+            let actual = coupncd(
+                settlement,
+                maturity,
+                CouponFrequency::Semiannual,
+                DayCountBasis::ActualActual,
+            );
+            assert_eq!(
+                actual.map(|date| date.serial(system)),
+                Ok(expected),
+                "system: {:?}",
+                system
+            );
+        }
+    }
+}
+
+pub fn coupnum(
+    settlement: ExcelDate,
+    maturity: ExcelDate,
+    frequency: CouponFrequency,
+    _basis: DayCountBasis,
+) -> Result<i64, XlsxError> {
+    let (_, _, remaining) = coupon_period(settlement, maturity, frequency)?;
+    Ok(remaining)
+}
+
+#[cfg(test)]
+mod tests_coupnum {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        settlement: ExcelDate,
+        maturity: ExcelDate,
+        frequency: CouponFrequency,
+        basis: DayCountBasis,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<i64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = coupnum(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 5] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(2),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Annual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(1),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Quarterly,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(4),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 5, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(1),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2010, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2020, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(20),
+            },
+        ];
+        for t in &test_cases {
+            let actual = coupnum(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+}
+
+pub fn couppcd(
+    settlement: ExcelDate,
+    maturity: ExcelDate,
+    frequency: CouponFrequency,
+    _basis: DayCountBasis,
+) -> Result<ExcelDate, XlsxError> {
+    let (previous, _, _) = coupon_period(settlement, maturity, frequency)?;
+    Ok(previous)
+}
+
+#[cfg(test)]
+mod tests_couppcd {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        settlement: ExcelDate,
+        maturity: ExcelDate,
+        frequency: CouponFrequency,
+        basis: DayCountBasis,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<ExcelDate, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 2] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = couppcd(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 6] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: ExcelDate::from_ymd(2010, 11, 15),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Annual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: ExcelDate::from_ymd(2010, 11, 15),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 3, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 30).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: ExcelDate::from_ymd(2010, 11, 30),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 3, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 30).unwrap(),
+                    frequency: CouponFrequency::Quarterly,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: ExcelDate::from_ymd(2011, 2, 28),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 3, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 29).unwrap(),
+                    frequency: CouponFrequency::Quarterly,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: ExcelDate::from_ymd(2011, 2, 28),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 5, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 11, 15).unwrap(),
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: ExcelDate::from_ymd(2011, 5, 15),
+            },
+        ];
+        for t in &test_cases {
+            let actual = couppcd(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_serials() {
+        let test_cases: [(DateSystem, i64, i64, i64); 2] = [
+            (DateSystem::Date1900, 40_568, 40_862, 40_497),
+            (DateSystem::Date1904, 39_106, 39_400, 39_035),
+        ];
+        for &(system, settlement, maturity, expected) in &test_cases {
+            let settlement = ExcelDate::from_serial(settlement, system).unwrap();
+            let maturity = ExcelDate::from_serial(maturity, system).unwrap();
+            let actual = couppcd(
+                settlement,
+                maturity,
+                CouponFrequency::Semiannual,
+                DayCountBasis::ActualActual,
+            );
+            assert_eq!(
+                actual.map(|date| date.serial(system)),
+                Ok(expected),
+                "system: {:?}",
+                system
+            );
+        }
+    }
+}
+
+const YIELD_MAX_ITERATIONS: usize = 100;
+const YIELD_TOLERANCE: f64 = 1e-10;
+
+struct BondTerms {
+    coupons: i64,
+    per_year: f64,
+    accrued: f64,
+    remaining: f64,
+}
+
+impl BondTerms {
+    fn new(
+        settlement: ExcelDate,
+        maturity: ExcelDate,
+        frequency: CouponFrequency,
+        basis: DayCountBasis,
+    ) -> Result<Self, XlsxError> {
+        let days = coupdays(settlement, maturity, frequency, basis)?;
+        Ok(BondTerms {
+            coupons: coupnum(settlement, maturity, frequency, basis)?,
+            per_year: frequency.per_year() as f64,
+            accrued: coupdaybs(settlement, maturity, frequency, basis)? as f64 / days,
+            remaining: coupdaysnc(settlement, maturity, frequency, basis)? as f64 / days,
+        })
+    }
+
+    fn price(&self, rate: f64, yld: f64, redemption: f64) -> (f64, f64) {
+        let coupon = 100.0 * rate / self.per_year;
+        let accrued_interest = coupon * self.accrued;
+
+        if self.coupons == 1 {
+            let discount = 1.0 + yld / self.per_year * self.remaining;
+            let price = (coupon + redemption) / discount - accrued_interest;
+            let dprice =
+                -(coupon + redemption) / (discount * discount) * self.remaining / self.per_year;
+            return (price, dprice);
+        }
+
+        let base = 1.0 + yld / self.per_year;
+        let last = (self.coupons - 1) as f64 + self.remaining;
+        let mut price = redemption * base.powf(-last) - accrued_interest;
+        let mut dprice = -redemption * last * base.powf(-last - 1.0) / self.per_year;
+        for k in 0..self.coupons {
+            let exponent = k as f64 + self.remaining;
+            price += coupon * base.powf(-exponent);
+            dprice -= coupon * exponent * base.powf(-exponent - 1.0) / self.per_year;
+        }
+        (price, dprice)
+    }
+}
+
+pub fn price(
+    settlement: ExcelDate,
+    maturity: ExcelDate,
+    rate: f64,
+    yld: f64,
+    redemption: f64,
+    frequency: CouponFrequency,
+    basis: DayCountBasis,
+) -> Result<f64, XlsxError> {
+    let terms = BondTerms::new(settlement, maturity, frequency, basis)?;
+    if rate < 0.0 || yld < 0.0 || redemption <= 0.0 {
+        return Err(XlsxError::Num);
+    }
+    Ok(terms.price(rate, yld, redemption).0)
+}
+
+#[cfg(test)]
+mod tests_price {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        settlement: ExcelDate,
+        maturity: ExcelDate,
+        rate: f64,
+        yld: f64,
+        redemption: f64,
+        frequency: CouponFrequency,
+        basis: DayCountBasis,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 4] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2017, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2008, 2, 15).unwrap(),
+                    rate: 0.0575,
+                    yld: 0.065,
+                    redemption: 100.0,
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2008, 2, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2017, 11, 15).unwrap(),
+                    rate: -0.0575,
+                    yld: 0.065,
+                    redemption: 100.0,
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2008, 2, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2017, 11, 15).unwrap(),
+                    rate: 0.0575,
+                    yld: -0.065,
+                    redemption: 100.0,
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2008, 2, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2017, 11, 15).unwrap(),
+                    rate: 0.0575,
+                    yld: 0.065,
+                    redemption: 0.0,
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = price(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.rate,
+                t.args.yld,
+                t.args.redemption,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 4] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2008, 2, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2017, 11, 15).unwrap(),
+                    rate: 0.0575,
+                    yld: 0.065,
+                    redemption: 100.0,
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Ok(94.63436162132213),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2008, 2, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2017, 11, 15).unwrap(),
+                    rate: 0.0575,
+                    yld: 0.065,
+                    redemption: 100.0,
+                    frequency: CouponFrequency::Quarterly,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(94.61509395213776),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 5, 15).unwrap(),
+                    rate: 0.05,
+                    yld: 0.06,
+                    redemption: 100.0,
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(99.68401417338413),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2008, 2, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2017, 11, 15).unwrap(),
+                    rate: 0.0,
+                    yld: 0.065,
+                    redemption: 100.0,
+                    frequency: CouponFrequency::Annual,
+                    basis: DayCountBasis::Actual365,
+                },
+                expected: Ok(54.11561415426078),
+            },
+        ];
+        for t in &test_cases {
+            let actual = price(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.rate,
+                t.args.yld,
+                t.args.redemption,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_serials() {
+        let test_cases: [(DateSystem, i64, i64); 2] = [
+            (DateSystem::Date1900, 39_493, 43_054),
+            (DateSystem::Date1904, 38_031, 41_592),
+        ];
+        for &(system, settlement, maturity) in &test_cases {
+            let settlement = ExcelDate::from_serial(settlement, system).unwrap();
+            let maturity = ExcelDate::from_serial(maturity, system).unwrap();
+            let actual = price(
+                settlement,
+                maturity,
+                0.0575,
+                0.065,
+                100.0,
+                CouponFrequency::Semiannual,
+                DayCountBasis::UsNasd30360,
+            );
+            assert_eq!(actual, Ok(94.63436162132213), "system: {:?}", system);
+        }
+    }
+}
+
+pub fn r#yield(
+    settlement: ExcelDate,
+    maturity: ExcelDate,
+    rate: f64,
+    pr: f64,
+    redemption: f64,
+    frequency: CouponFrequency,
+    basis: DayCountBasis,
+) -> Result<f64, XlsxError> {
+    let terms = BondTerms::new(settlement, maturity, frequency, basis)?;
+    if rate < 0.0 || pr <= 0.0 || redemption <= 0.0 {
+        return Err(XlsxError::Num);
+    }
+
+    if terms.coupons == 1 {
+        let coupon = rate / terms.per_year;
+        let dirty_price = pr / 100.0 + terms.accrued * coupon;
+        return Ok(
+            (redemption / 100.0 + coupon - dirty_price) / dirty_price * terms.per_year
+                / terms.remaining,
+        );
+    }
+
+    let price_gap = |yld: f64| {
+        let (actual, dprice) = terms.price(rate, yld, redemption);
+        (actual - pr, dprice)
+    };
+    newton(price_gap, rate, YIELD_MAX_ITERATIONS, YIELD_TOLERANCE).ok_or(XlsxError::Num)
+}
+
+#[cfg(test)]
+mod tests_yield {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestArgs {
+        settlement: ExcelDate,
+        maturity: ExcelDate,
+        rate: f64,
+        pr: f64,
+        redemption: f64,
+        frequency: CouponFrequency,
+        basis: DayCountBasis,
+    }
+
+    struct TestData {
+        args: TestArgs,
+        expected: Result<f64, XlsxError>,
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let test_cases: [TestData; 4] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2016, 11, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2008, 2, 15).unwrap(),
+                    rate: 0.0575,
+                    pr: 95.04287,
+                    redemption: 100.0,
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2008, 2, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2016, 11, 15).unwrap(),
+                    rate: -0.0575,
+                    pr: 95.04287,
+                    redemption: 100.0,
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2008, 2, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2016, 11, 15).unwrap(),
+                    rate: 0.0575,
+                    pr: 0.0,
+                    redemption: 100.0,
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Err(XlsxError::Num),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2008, 2, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2016, 11, 15).unwrap(),
+                    rate: 0.0575,
+                    pr: 95.04287,
+                    redemption: -100.0,
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Err(XlsxError::Num),
+            },
+        ];
+        for t in &test_cases {
+            let actual = r#yield(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.rate,
+                t.args.pr,
+                t.args.redemption,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let test_cases: [TestData; 3] = [
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2008, 2, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2016, 11, 15).unwrap(),
+                    rate: 0.0575,
+                    pr: 95.04287,
+                    redemption: 100.0,
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::UsNasd30360,
+                },
+                expected: Ok(0.06500000688075483),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2011, 1, 25).unwrap(),
+                    maturity: ExcelDate::from_ymd(2011, 5, 15).unwrap(),
+                    rate: 0.05,
+                    pr: 99.5,
+                    redemption: 100.0,
+                    frequency: CouponFrequency::Semiannual,
+                    basis: DayCountBasis::ActualActual,
+                },
+                expected: Ok(0.06613665105444909),
+            },
+            TestData {
+                args: TestArgs {
+                    settlement: ExcelDate::from_ymd(2008, 2, 15).unwrap(),
+                    maturity: ExcelDate::from_ymd(2017, 11, 15).unwrap(),
+                    rate: 0.0,
+                    pr: 60.0,
+                    redemption: 100.0,
+                    frequency: CouponFrequency::Annual,
+                    basis: DayCountBasis::Actual365,
+                },
+                expected: Ok(0.05378526018295802),
+            },
+        ];
+        for t in &test_cases {
+            let actual = r#yield(
+                t.args.settlement,
+                t.args.maturity,
+                t.args.rate,
+                t.args.pr,
+                t.args.redemption,
+                t.args.frequency,
+                t.args.basis,
+            );
+            assert_eq!(actual, t.expected, "args: {:#?}", t.args);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_with_price() {
+        let frequencies = [
+            CouponFrequency::Annual,
+            CouponFrequency::Semiannual,
+            CouponFrequency::Quarterly,
+        ];
+        let bases = [
+            DayCountBasis::UsNasd30360,
+            DayCountBasis::ActualActual,
+            DayCountBasis::Actual360,
+            DayCountBasis::Actual365,
+            DayCountBasis::European30360,
+        ];
+        for &(settlement, maturity) in &[(39_493, 43_054), (40_568, 40_678), (40_617, 40_877)] {
+            let settlement = ExcelDate::from_serial(settlement, DateSystem::Date1900).unwrap();
+            let maturity = ExcelDate::from_serial(maturity, DateSystem::Date1900).unwrap();
+            for &frequency in &frequencies {
+                for &basis in &bases {
+                    let pr = price(settlement, maturity, 0.0575, 0.065, 100.0, frequency, basis)
+                        .unwrap();
+                    let actual =
+                        r#yield(settlement, maturity, 0.0575, pr, 100.0, frequency, basis).unwrap();
+                    assert!(
+                        (actual - 0.065).abs() < 1e-9,
+                        "settlement: {:?}, maturity: {:?}, frequency: {:?}, basis: {:?}, yield: {}",
+                        settlement,
+                        maturity,
+                        frequency,
+                        basis,
+                        actual
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_serials() {
+        let test_cases: [(DateSystem, i64, i64); 2] = [
+            (DateSystem::Date1900, 39_493, 42_689),
+            (DateSystem::Date1904, 38_031, 41_227),
+        ];
+        for &(system, settlement, maturity) in &test_cases {
+            let settlement = ExcelDate::from_serial(settlement, system).unwrap();
+            let maturity = ExcelDate::from_serial(maturity, system).unwrap();
+            let actual = r#yield(
+                settlement,
+                maturity,
+                0.0575,
+                95.04287,
+                100.0,
+                CouponFrequency::Semiannual,
+                DayCountBasis::UsNasd30360,
+            );
+            assert_eq!(actual, Ok(0.06500000688075483), "system: {:?}", system);
+        }
+    }
+}